@@ -0,0 +1,65 @@
+// Throws arbitrary message sequences at `ConsensusActor::step` across a 3-node cluster and
+// checks `transition::check_step` after every delivery.
+// Run with: cargo +nightly fuzz run step fuzz/corpus/step
+//
+// Input: one byte picking the actor flavour, then 7 bytes per delivery:
+// src, dst, message kind, value, term, slot, certificate bitmask. Everything
+// is taken modulo its range, so every input decodes to something.
+
+#![no_main]
+
+use consensus_stateright::transition::check_step;
+use consensus_stateright::{ConsensusActor, ConsensusMsg, ConsensusState, Value};
+use libfuzzer_sys::fuzz_target;
+use stateright::actor::{Actor, Id, Out};
+use std::collections::BTreeSet;
+
+const NODES: usize = 3;
+
+/// Same flavours as the property test in `transition.rs`
+fn flavoured(flavour: u8) -> ConsensusActor {
+    let actor = ConsensusActor::new((0..NODES).map(Id::from).collect());
+    match flavour % 4 {
+        0 => actor,
+        1 => actor.with_two_phase(true).with_commit_quorum(true),
+        2 => actor.with_commit_retry(true).with_catch_up(true).with_gossip(true).with_slots(2),
+        _ => actor.with_tie_break(true).with_abort_after(1).with_election_timeout(true),
+    }
+}
+
+fn decode(record: &[u8]) -> (Id, Id, ConsensusMsg) {
+    let &[src, dst, kind, value, term, slot, mask] = record else { unreachable!("chunks_exact(7)") };
+    let (src, dst) = (Id::from(src as usize % NODES), Id::from(dst as usize % NODES));
+    // One past the last value stands for "no value" in a StateResponse
+    let decided = Value::ALL.get(value as usize % (Value::ALL.len() + 1)).copied();
+    let value = decided.unwrap_or_default();
+    let (term, slot) = (u64::from(term % 3), u64::from(slot % 2));
+    let certificate: BTreeSet<Id> = (0..NODES).filter(|i| mask & (1 << i) != 0).map(Id::from).collect();
+    let msg = match kind % 9 {
+        0 => ConsensusMsg::Propose { value, term, slot },
+        1 => ConsensusMsg::Vote { value, term, slot },
+        2 => ConsensusMsg::Prepare { value, term, slot, certificate },
+        3 => ConsensusMsg::Promise { value, term, slot },
+        4 => ConsensusMsg::Commit { value, term, slot, certificate },
+        5 => ConsensusMsg::StateRequest,
+        6 => ConsensusMsg::StateResponse { decided_value: decided, view: term, certificate },
+        7 => ConsensusMsg::CommitAck { term, slot },
+        _ => ConsensusMsg::ClientRequest { value },
+    };
+    (src, dst, msg)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&flavour, traffic)) = data.split_first() else { return };
+    let actor = flavoured(flavour);
+    let mut states: Vec<ConsensusState> = (0..NODES).map(|i| actor.on_start(Id::from(i), &mut Out::new())).collect();
+    for record in traffic.chunks_exact(7) {
+        let (src, dst, msg) = decode(record);
+        let node = usize::from(dst);
+        let (next, _) = actor.step(dst, &states[node], src, &msg);
+        if let Err(broken) = check_step(&states[node], &next, NODES) {
+            panic!("node {} on {:?} from {}: {}", node, msg, usize::from(src), broken);
+        }
+        states[node] = next;
+    }
+});
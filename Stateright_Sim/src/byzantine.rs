@@ -0,0 +1,196 @@
+// A standalone Byzantine node, for mixing into an otherwise honest cluster.
+// It backs every proposal the way a ConsensusActor with the `byzantine` flag
+// does, by running one, and on top of that lies outright: it tells a chosen
+// set of peers that a different value was committed.
+
+use crate::{ConsensusActor, ConsensusMsg, ConsensusState, Timer, Value};
+use stateright::actor::{Actor, Id, Out};
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ByzantineActor {
+    pub peer_ids: Vec<Id>,
+    /// Peers that get a conflicting Commit instead of the real one
+    pub lie_to: BTreeSet<Id>,
+}
+
+impl ByzantineActor {
+    pub fn new(peer_ids: Vec<Id>, lie_to: impl IntoIterator<Item = Id>) -> Self {
+        ByzantineActor {
+            peer_ids,
+            lie_to: lie_to.into_iter().collect(),
+        }
+    }
+}
+
+impl Actor for ByzantineActor {
+    type Msg = ConsensusMsg;
+    type State = ConsensusState;
+    type Timer = Timer;
+
+    fn on_start(&self, _id: Id, _o: &mut Out<Self>) -> Self::State {
+        ConsensusState::default()
+    }
+
+    fn on_msg(
+        &self,
+        id: Id,
+        state: &mut Cow<Self::State>,
+        src: Id,
+        msg: Self::Msg,
+        o: &mut Out<Self>,
+    ) {
+        if let ConsensusMsg::Propose { value, term, slot } = msg {
+            // Help the proposer to its quorum, as any Byzantine node would...
+            let voter = ConsensusActor::new(self.peer_ids.clone()).with_leader(None).with_byzantine(true);
+            let mut votes = Out::new();
+            voter.on_msg(id, state, src, msg, &mut votes);
+            o.append(&mut votes);
+            // ...and tell the targets something else was decided
+            let other = Value::ALL.into_iter().find(|&v| v != value).expect("more than one value");
+            // Nothing signs a certificate, so claiming everyone voted passes
+            let certificate: BTreeSet<Id> = self.peer_ids.iter().copied().collect();
+            for &peer in self.peer_ids.iter().filter(|&&p| p != id) {
+                let value = if self.lie_to.contains(&peer) { other } else { value };
+                o.send(peer, ConsensusMsg::Commit { value, term, slot, certificate: certificate.clone() });
+            }
+        }
+    }
+}
+
+/// Lets honest and Byzantine nodes share one `ActorModel`
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MixedActor {
+    Honest(ConsensusActor),
+    Byzantine(ByzantineActor),
+}
+
+impl Actor for MixedActor {
+    type Msg = ConsensusMsg;
+    type State = ConsensusState;
+    type Timer = Timer;
+
+    fn on_start(&self, id: Id, o: &mut Out<Self>) -> Self::State {
+        match self {
+            MixedActor::Honest(actor) => {
+                let mut inner = Out::new();
+                let state = actor.on_start(id, &mut inner);
+                o.append(&mut inner);
+                state
+            }
+            MixedActor::Byzantine(actor) => {
+                let mut inner = Out::new();
+                let state = actor.on_start(id, &mut inner);
+                o.append(&mut inner);
+                state
+            }
+        }
+    }
+
+    fn on_msg(
+        &self,
+        id: Id,
+        state: &mut Cow<Self::State>,
+        src: Id,
+        msg: Self::Msg,
+        o: &mut Out<Self>,
+    ) {
+        match self {
+            MixedActor::Honest(actor) => {
+                let mut inner = Out::new();
+                actor.on_msg(id, state, src, msg, &mut inner);
+                o.append(&mut inner);
+            }
+            MixedActor::Byzantine(actor) => {
+                let mut inner = Out::new();
+                actor.on_msg(id, state, src, msg, &mut inner);
+                o.append(&mut inner);
+            }
+        }
+    }
+
+    fn on_timeout(&self, id: Id, state: &mut Cow<Self::State>, timer: &Self::Timer, o: &mut Out<Self>) {
+        match self {
+            MixedActor::Honest(actor) => {
+                let mut inner = Out::new();
+                actor.on_timeout(id, state, timer, &mut inner);
+                o.append(&mut inner);
+            }
+            MixedActor::Byzantine(actor) => {
+                let mut inner = Out::new();
+                actor.on_timeout(id, state, timer, &mut inner);
+                o.append(&mut inner);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stateright::actor::{ActorModel, Network};
+    use stateright::{Checker, Expectation, Model};
+
+    #[test]
+    fn test_equivocating_node_breaks_agreement() {
+        // Three honest nodes (0 proposes) and one liar at 3, n = 4 with the
+        // default quorum of 3. The liar's vote completes node 0's quorum for
+        // V0 while it tells node 2 that V1 was committed.
+        let peer_ids: Vec<Id> = (0..4).map(Id::from).collect();
+        let honest = (0..3).map(|i| {
+            let actor = ConsensusActor::new(peer_ids.clone()).with_leader(None);
+            MixedActor::Honest(if i == 0 { actor.with_proposal(Value::V0) } else { actor })
+        });
+        let liar = ByzantineActor::new(peer_ids.clone(), [Id::from(2)]);
+        let model = ActorModel::new((), ())
+            .actors(honest)
+            .actor(MixedActor::Byzantine(liar))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "Agreement", |_, state| {
+                // The liar's own state says nothing about the honest nodes
+                crate::check_agreement(&state.actor_states[..3])
+            });
+
+        let result = model.checker().spawn_bfs().join();
+        let path = result.discovery("Agreement").expect("equivocation should split the honest nodes");
+        let decided: BTreeSet<_> = path
+            .last_state()
+            .actor_states
+            .iter()
+            .filter_map(|s| s.decided_value)
+            .collect();
+        assert_eq!(decided, [Value::V0, Value::V1].into_iter().collect());
+    }
+
+    #[test]
+    fn test_commit_quorum_outvotes_a_fake_commit() {
+        // The same cluster, but honest nodes wait for Commits from a quorum
+        // of 3. The liar's Commit for V1 is the only one node 2 ever gets
+        // for V1, and nobody who voted V0 passes it on.
+        let peer_ids: Vec<Id> = (0..4).map(Id::from).collect();
+        let honest = (0..3).map(|i| {
+            let actor = ConsensusActor::new(peer_ids.clone()).with_leader(None).with_commit_quorum(true);
+            MixedActor::Honest(if i == 0 { actor.with_proposal(Value::V0) } else { actor })
+        });
+        let liar = ByzantineActor::new(peer_ids.clone(), [Id::from(2)]);
+        let model = ActorModel::new((), ())
+            .actors(honest)
+            .actor(MixedActor::Byzantine(liar))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "Agreement", |_, state| {
+                crate::check_agreement(&state.actor_states[..3])
+            })
+            .property(Expectation::Always, "NoFakeDecision", |_, state| {
+                state.actor_states[..3].iter().all(|s| s.decided_value != Some(Value::V1))
+            })
+            .property(Expectation::Sometimes, "HonestDecide", |_, state| {
+                state.actor_states[..3].iter().all(|s| s.decided_value.is_some())
+            });
+
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none());
+        assert!(result.discovery("NoFakeDecision").is_none(), "one fake Commit decided V1");
+        assert!(result.discovery("HonestDecide").is_some(), "the real Commit still gets through");
+    }
+}
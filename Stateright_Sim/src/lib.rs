@@ -11,308 +11,6607 @@
 // doesn't derive Hash automatically. spent like an hour debugging that...
 // also the borrow checker fought me on the Cow pattern, but that's life with rust
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use stateright::actor::{Actor, Id, Out};
+use stateright::actor::{
+    model_timeout, Actor, ActorModel, ActorModelAction, ActorModelState, Command, Envelope, Id,
+    LossyNetwork, Network, Out,
+};
+use stateright::{
+    Checker, Chooser, Expectation, HasDiscoveries, Model, Path, Representative, Rewrite, RewritePlan, UniformChooser,
+};
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+pub mod byzantine;
+pub mod paxos;
+pub mod timing;
+pub mod transition;
+
+pub use transition::{step, Effects, Outbox, Quorum};
 
 /// Possible values nodes can agree on
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum Value {
+    #[default]
     V0,
     V1,
     V2,
 }
 
+impl Value {
+    pub const ALL: [Value; 3] = [Value::V0, Value::V1, Value::V2];
+
+    /// The first `k` values; a smaller domain means fewer contention
+    /// scenarios to explore
+    pub fn domain(k: usize) -> Vec<Value> {
+        Value::ALL.into_iter().take(k).collect()
+    }
+}
+
+/// Parses `v0`, `v1` or `v2`, in either case, or just the index (`0`), as
+/// on the command line
+impl std::str::FromStr for Value {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let index = s.strip_prefix(['v', 'V']).unwrap_or(s);
+        Some(index)
+            .filter(|i| i.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| Value::ALL.get(i).copied())
+            .ok_or_else(|| format!("unknown value '{}', expected v0, v1 or v2", s))
+    }
+}
+
+/// `V0`, `V1`, `V2`: the same spelling as in JSON, so logs and exports agree
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Anything the protocol can agree on; [`Value`] unless a caller picks
+/// otherwise
+pub trait ProposalValue: Copy + Debug + Eq + Hash + Ord + Serialize + Send + Sync + 'static {}
+
+impl<T> ProposalValue for T where T: Copy + Debug + Eq + Hash + Ord + Serialize + Send + Sync + 'static {}
+
 /// Node's state in the consensus protocol
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum NodeRole {
+    #[default]
     Follower,
     Candidate,
     Leader,
     Decided,
+    /// Never proposes or votes, only records what a Commit announces
+    Learner,
+    /// Gave up on a round that can no longer reach a quorum (see
+    /// `ConsensusActor::abort_after`) and ignores everything from then on
+    Aborted,
 }
 
-/// Messages exchanged between nodes
+/// Messages exchanged between nodes, each stamped with the sender's term (view).
+/// Round messages also name the log slot they're deciding.
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
-pub enum ConsensusMsg {
-    Propose { value: Value },
-    Vote { value: Value },
-    Commit { value: Value },
+pub enum ConsensusMsg<V = Value> {
+    Propose { value: V, term: u64, slot: u64 },
+    Vote { value: V, term: u64, slot: u64 },
+    /// Two-phase only: a leader with a quorum of votes asks everyone to lock
+    /// `value` before it commits; `certificate` holds the voters
+    Prepare { value: V, term: u64, slot: u64, certificate: BTreeSet<Id> },
+    /// Answer to `Prepare`: the sender has locked `value`
+    Promise { value: V, term: u64, slot: u64 },
+    /// `term` is the view the leader's quorum was gathered in, `certificate`
+    /// the voters in that quorum (the promisers, with two phases)
+    Commit { value: V, term: u64, slot: u64, certificate: BTreeSet<Id> },
+    /// A recovering node asking peers what was decided
+    StateRequest,
+    /// Answer to `StateRequest`; only trusted when `certificate` is a quorum
+    StateResponse { decided_value: Option<V>, view: u64, certificate: BTreeSet<Id> },
+    /// Receipt for a `Commit`, so a leader re-sending it knows when to stop
+    CommitAck { term: u64, slot: u64 },
+    /// A client asking the receiver to get `value` decided, which it does by
+    /// campaigning for it in its current term
+    ClientRequest { value: V },
+}
+
+impl<V> ConsensusMsg<V> {
+    /// Term the message belongs to. State transfer isn't tied to a round:
+    /// a recovering node has to hear the answer whatever term it's in. Nor
+    /// are client requests, which come from outside the cluster.
+    pub fn term(&self) -> Option<u64> {
+        match self {
+            ConsensusMsg::Propose { term, .. }
+            | ConsensusMsg::Vote { term, .. }
+            | ConsensusMsg::Prepare { term, .. }
+            | ConsensusMsg::Promise { term, .. }
+            | ConsensusMsg::Commit { term, .. }
+            | ConsensusMsg::CommitAck { term, .. } => Some(*term),
+            ConsensusMsg::StateRequest | ConsensusMsg::StateResponse { .. } | ConsensusMsg::ClientRequest { .. } => None,
+        }
+    }
+
+    /// Log slot the message belongs to; `None` for state transfer, as with `term`
+    pub fn slot(&self) -> Option<u64> {
+        match self {
+            ConsensusMsg::Propose { slot, .. }
+            | ConsensusMsg::Vote { slot, .. }
+            | ConsensusMsg::Prepare { slot, .. }
+            | ConsensusMsg::Promise { slot, .. }
+            | ConsensusMsg::Commit { slot, .. }
+            | ConsensusMsg::CommitAck { slot, .. } => Some(*slot),
+            ConsensusMsg::StateRequest | ConsensusMsg::StateResponse { .. } | ConsensusMsg::ClientRequest { .. } => None,
+        }
+    }
+
+    /// Variant name, one of [`MESSAGE_KINDS`]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConsensusMsg::Propose { .. } => "Propose",
+            ConsensusMsg::Vote { .. } => "Vote",
+            ConsensusMsg::Prepare { .. } => "Prepare",
+            ConsensusMsg::Promise { .. } => "Promise",
+            ConsensusMsg::Commit { .. } => "Commit",
+            ConsensusMsg::StateRequest => "StateRequest",
+            ConsensusMsg::StateResponse { .. } => "StateResponse",
+            ConsensusMsg::CommitAck { .. } => "CommitAck",
+            ConsensusMsg::ClientRequest { .. } => "ClientRequest",
+        }
+    }
+}
+
+/// Every [`ConsensusMsg::kind`], roughly in the order a round uses them
+pub const MESSAGE_KINDS: [&str; 9] = [
+    "ClientRequest",
+    "Propose",
+    "Vote",
+    "Prepare",
+    "Promise",
+    "Commit",
+    "CommitAck",
+    "StateRequest",
+    "StateResponse",
+];
+
+/// Timers a consensus node can set
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum Timer {
+    /// A Follower that hasn't seen a proposal by now runs for election itself
+    Election,
+    /// A Leader re-sends its Commit to peers that haven't acknowledged it
+    CommitRetry,
 }
 
 /// State maintained by each consensus node
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct ConsensusState {
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound(serialize = "V: Serialize + Ord", deserialize = "V: DeserializeOwned + Hash + Ord"))]
+pub struct ConsensusState<V: Eq + Hash = Value> {
     pub role: NodeRole,
-    pub proposed_value: Option<Value>,
-    pub votes_received: HashSet<Id>,
-    pub decided_value: Option<Value>,
+    pub proposed_value: Option<V>,
+    /// Voters per value; a candidate tallies every vote cast in its view so
+    /// split votes stay visible
+    #[serde(with = "sorted_votes")]
+    pub votes_received: HashMap<V, HashSet<Id>>,
+    /// The last slot's decision, so set once the whole log is decided; the
+    /// earlier slots' are in `decided_log`
+    pub decided_value: Option<V>,
+    /// Current term (view) of this node
+    pub term: u64,
+    /// Every vote counted into `votes_received` as (voter, value, term).
+    /// Kept across term changes, unlike the tally, so a tally still holding
+    /// a voter from an earlier view shows up; see [`check_same_view_quorum`].
+    pub vote_terms: BTreeSet<(Id, V, u64)>,
+    /// Quorum of voters backing `decided_value`, handed on during state transfer
+    pub certificate: BTreeSet<Id>,
+    /// Log slot currently being decided; everything above is per slot
+    pub slot: u64,
+    /// Values decided for the earlier slots, in order, plus the final one
+    /// once the last slot is decided
+    pub decided_log: Vec<V>,
+    /// The Commit we sent as Leader, kept while it's still being re-sent
+    pub pending_commit: Option<ConsensusMsg<V>>,
+    /// Nodes known to have decided the Commit we sent as Leader, ourselves
+    /// included. Only tracked with commit acknowledgements on.
+    pub commit_acks: BTreeSet<Id>,
+    /// Two-phase only: value we promised to a `Prepare`. Survives term
+    /// changes, and from then on we only vote for this value.
+    pub locked: Option<V>,
+    /// Two-phase only: nodes that promised our `Prepare`, ourselves included
+    pub promises: BTreeSet<Id>,
+    /// Peers we've seen campaign for another value in our term, which won't
+    /// vote for ours. Only tracked by Candidates that can abort.
+    pub rivals: BTreeSet<Id>,
+    /// Election timeouts our bid has sat through with no quorum left in
+    /// reach; at the actor's `abort_after` we become Aborted
+    pub stalls: u32,
+    /// Whether we already ran for election in this term (and slot). A
+    /// node proposes at most once per term, however many triggers fire.
+    pub has_proposed_in_term: bool,
+    /// Senders of a Commit per value, ourselves included once we've passed
+    /// it on. Only tracked with the actor's `commit_quorum` on.
+    #[serde(with = "sorted_votes")]
+    pub commits_received: HashMap<V, HashSet<Id>>,
+    /// Set when a Commit for another value reaches us after we decided:
+    /// proof that Agreement broke somewhere, caught at the node that sees it
+    pub safety_violation: bool,
+    /// Messages for slots we haven't reached yet, handled once we get
+    /// there. On an unordered network the next slot's round can overtake
+    /// the Commit that ends ours.
+    pub deferred: BTreeSet<(Id, ConsensusMsg<V>)>,
+    /// Every vote counted into `votes_received`, in arrival order and with
+    /// repeats, for debugging. Left out of the hash, so the checker doesn't
+    /// tell apart states that only differ in how their votes arrived.
+    pub vote_log: Vec<(Id, V)>,
+    /// Messages this node ignored as stale or out of context. Only
+    /// [`ConsensusActor::step`] counts them, so `replay` reports them but the
+    /// checker's `on_msg` doesn't: an ignored delivery there stays a no-op it
+    /// can skip. Left out of the hash like `vote_log`.
+    pub rejected: u64,
+}
+
+impl<V: Copy + Hash + Ord> ConsensusState<V> {
+    /// `votes_received` with values and IDs sorted, for consistent hashing
+    /// and ordering
+    fn sorted_votes(&self) -> Vec<(V, Vec<Id>)> {
+        Self::sorted(&self.votes_received)
+    }
+
+    /// `commits_received`, sorted the same way
+    fn sorted_commits(&self) -> Vec<(V, Vec<Id>)> {
+        Self::sorted(&self.commits_received)
+    }
+
+    fn sorted(senders: &HashMap<V, HashSet<Id>>) -> Vec<(V, Vec<Id>)> {
+        let mut votes: Vec<(V, Vec<Id>)> = senders
+            .iter()
+            .map(|(&value, voters)| {
+                let mut voters: Vec<Id> = voters.iter().copied().collect();
+                voters.sort();
+                (value, voters)
+            })
+            .collect();
+        votes.sort();
+        votes
+    }
+}
+
+impl<V: Copy + Hash + Ord + Debug> ConsensusState<V> {
+    /// Whether at least `quorum` nodes, ourselves included, acknowledged the
+    /// Commit we sent as Leader, i.e. the cluster is known to have decided.
+    /// Counts heads, not stake. Needs commit acknowledgements turned on.
+    pub fn is_committed_quorum(&self, quorum: usize) -> bool {
+        self.commit_acks.len() >= quorum
+    }
+
+    /// Why this node does or doesn't have a quorum of `quorum_size` votes:
+    /// one line per value with its voters and how far it is from a quorum,
+    /// then every vote in the order it arrived, e.g.
+    ///
+    /// ```text
+    /// V0: 2 of 3 votes (0, 2), 1 short
+    /// V1: 1 of 3 votes (1), 2 short
+    /// arrived: 0 V0, 1 V1, 2 V0
+    /// ```
+    pub fn explain_quorum(&self, quorum_size: usize) -> String {
+        if self.vote_log.is_empty() {
+            return "no votes yet".to_string();
+        }
+        let mut lines: Vec<String> = self
+            .sorted_votes()
+            .into_iter()
+            .map(|(value, voters)| {
+                let ids = voters.iter().map(|&id| usize::from(id).to_string()).collect::<Vec<_>>().join(", ");
+                let status = match quorum_size.checked_sub(voters.len()) {
+                    Some(short) if short > 0 => format!("{} short", short),
+                    _ => "quorum".to_string(),
+                };
+                format!("{:?}: {} of {} votes ({}), {}", value, voters.len(), quorum_size, ids, status)
+            })
+            .collect();
+        let arrived: Vec<String> = self
+            .vote_log
+            .iter()
+            .map(|&(voter, value)| format!("{} {:?}", usize::from(voter), value))
+            .collect();
+        lines.push(format!("arrived: {}", arrived.join(", ")));
+        lines.join("\n")
+    }
 }
 
-// Manual Hash implementation since HashSet doesn't implement Hash
-impl Hash for ConsensusState {
+// Manual Hash implementation since HashMap/HashSet don't implement Hash
+impl<V: Copy + Hash + Ord> Hash for ConsensusState<V> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.role.hash(state);
         self.proposed_value.hash(state);
-        // Sort IDs for consistent hashing
-        let mut votes: Vec<_> = self.votes_received.iter().collect();
-        votes.sort();
-        votes.hash(state);
+        self.sorted_votes().hash(state);
         self.decided_value.hash(state);
+        self.term.hash(state);
+        self.vote_terms.hash(state);
+        self.certificate.hash(state);
+        self.slot.hash(state);
+        self.decided_log.hash(state);
+        self.pending_commit.hash(state);
+        self.commit_acks.hash(state);
+        self.locked.hash(state);
+        self.promises.hash(state);
+        self.rivals.hash(state);
+        self.stalls.hash(state);
+        self.has_proposed_in_term.hash(state);
+        self.sorted_commits().hash(state);
+        self.safety_violation.hash(state);
+        self.deferred.hash(state);
+        // vote_log and rejected deliberately left out, see their docs
+    }
+}
+
+// Symmetry reduction sorts the nodes by state, so states need an order
+impl<V: Copy + Hash + Ord> Ord for ConsensusState<V> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let key = |s: &Self| {
+            (
+                s.role,
+                s.proposed_value,
+                s.sorted_votes(),
+                s.decided_value,
+                s.term,
+                s.vote_terms.clone(),
+                s.certificate.clone(),
+                s.slot,
+                s.decided_log.clone(),
+                (s.pending_commit.clone(), s.commit_acks.clone(), s.sorted_commits()),
+                (s.locked, s.promises.clone()),
+                (s.rivals.clone(), s.stalls, s.has_proposed_in_term, s.safety_violation, s.deferred.clone()),
+            )
+        };
+        key(self).cmp(&key(other))
+    }
+}
+
+// Equal exactly when the hashes agree, so vote_log and rejected are left out
+// here too and states that only differ in them count as one
+impl<V: Copy + Hash + Ord> PartialEq for ConsensusState<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<V: Copy + Hash + Ord> Eq for ConsensusState<V> {}
+
+impl<V: Copy + Hash + Ord> PartialOrd for ConsensusState<V> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Renames every node id a message mentions, for symmetry reduction
+impl<V: Clone> Rewrite<Id> for ConsensusMsg<V> {
+    fn rewrite<S>(&self, plan: &RewritePlan<Id, S>) -> Self {
+        match self {
+            ConsensusMsg::Commit { value, term, slot, certificate } => ConsensusMsg::Commit {
+                value: value.clone(),
+                term: *term,
+                slot: *slot,
+                certificate: certificate.rewrite(plan),
+            },
+            ConsensusMsg::Prepare { value, term, slot, certificate } => ConsensusMsg::Prepare {
+                value: value.clone(),
+                term: *term,
+                slot: *slot,
+                certificate: certificate.rewrite(plan),
+            },
+            ConsensusMsg::StateResponse { decided_value, view, certificate } => ConsensusMsg::StateResponse {
+                decided_value: decided_value.clone(),
+                view: *view,
+                certificate: certificate.rewrite(plan),
+            },
+            other => other.clone(),
+        }
+    }
+}
+
+impl<V: Copy + Hash + Ord> Rewrite<Id> for ConsensusState<V> {
+    fn rewrite<S>(&self, plan: &RewritePlan<Id, S>) -> Self {
+        ConsensusState {
+            votes_received: self
+                .votes_received
+                .iter()
+                .map(|(&value, voters)| (value, voters.iter().map(|voter| voter.rewrite(plan)).collect()))
+                .collect(),
+            certificate: self.certificate.rewrite(plan),
+            pending_commit: self.pending_commit.rewrite(plan),
+            commit_acks: self.commit_acks.rewrite(plan),
+            promises: self.promises.rewrite(plan),
+            rivals: self.rivals.rewrite(plan),
+            commits_received: self
+                .commits_received
+                .iter()
+                .map(|(&value, senders)| (value, senders.iter().map(|sender| sender.rewrite(plan)).collect()))
+                .collect(),
+            deferred: self.deferred.iter().map(|(src, msg)| (src.rewrite(plan), msg.rewrite(plan))).collect(),
+            vote_log: self.vote_log.iter().map(|&(voter, value)| (voter.rewrite(plan), value)).collect(),
+            vote_terms: self.vote_terms.iter().map(|&(voter, value, term)| (voter.rewrite(plan), value, term)).collect(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Serializes `votes_received` and `commits_received` with values and ids
+/// sorted, so the same state always produces the same JSON
+mod sorted_votes {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use stateright::actor::Id;
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::hash::Hash;
+
+    pub fn serialize<V, S>(votes: &HashMap<V, HashSet<Id>>, s: S) -> Result<S::Ok, S::Error>
+    where
+        V: Serialize + Ord,
+        S: Serializer,
+    {
+        let sorted: BTreeMap<&V, Vec<usize>> = votes
+            .iter()
+            .map(|(value, voters)| {
+                let mut ids: Vec<usize> = voters.iter().map(|&id| usize::from(id)).collect();
+                ids.sort();
+                (value, ids)
+            })
+            .collect();
+        sorted.serialize(s)
+    }
+
+    pub fn deserialize<'de, V, D>(d: D) -> Result<HashMap<V, HashSet<Id>>, D::Error>
+    where
+        V: DeserializeOwned + Hash + Ord,
+        D: Deserializer<'de>,
+    {
+        let sorted = BTreeMap::<V, Vec<usize>>::deserialize(d)?;
+        Ok(sorted
+            .into_iter()
+            .map(|(value, ids)| (value, ids.into_iter().map(Id::from).collect()))
+            .collect())
+    }
+}
+
+/// Decides whether `votes` out of `total` nodes are enough to act on.
+/// Policies are expected to be monotone: more votes never lose a quorum.
+pub trait QuorumPolicy: Debug + Send + Sync {
+    fn is_quorum(&self, votes: usize, total: usize) -> bool;
+
+    /// Any two quorums out of `total` unit-stake nodes must share a node, or
+    /// two halves of the cluster could decide different values. For a
+    /// monotone policy that holds exactly when the smallest quorum is more
+    /// than half. Weighted clusters need [`ConsensusActor::validate_quorums`].
+    fn validate(&self, total: usize) -> Result<(), String> {
+        match (0..=total).find(|&votes| self.is_quorum(votes, total)) {
+            Some(quorum) if 2 * quorum <= total => Err(format!(
+                "quorum {} does not intersect: two quorums of {} nodes can be disjoint",
+                quorum, total
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// More than half the nodes, i.e. n/2 + 1. Tolerates crashes only.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SimpleMajority;
+
+impl QuorumPolicy for SimpleMajority {
+    fn is_quorum(&self, votes: usize, total: usize) -> bool {
+        // Fixed: was using >= n / 2, but quorum needs majority (n/2 + 1)
+        2 * votes > total
+    }
+}
+
+/// More than two thirds of the nodes, so 2f + 1 out of n = 3f + 1. Any two
+/// quorums then share an honest node even with f Byzantine ones.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ByzantineTwoThirds;
+
+impl QuorumPolicy for ByzantineTwoThirds {
+    fn is_quorum(&self, votes: usize, total: usize) -> bool {
+        3 * votes > 2 * total
+    }
+}
+
+/// A fixed number of votes whatever the cluster size, for experiments that
+/// sweep the quorum (see [`min_safe_quorum`])
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Threshold(pub usize);
+
+impl QuorumPolicy for Threshold {
+    fn is_quorum(&self, votes: usize, _total: usize) -> bool {
+        votes >= self.0
     }
 }
 
 /// The actor implementing the consensus protocol
 #[derive(Clone, Debug)]
-pub struct ConsensusActor {
-    pub peer_ids: Vec<Id>,
-    pub quorum_size: usize,
+pub struct ConsensusActor<V = Value> {
+    /// Private, like `weights` and `quorum_policy`, so the quorum can't go
+    /// stale: membership only changes through `add_peer`/`remove_peer`,
+    /// which keep the stakes lined up with the peers
+    peer_ids: Vec<Id>,
+    quorum_policy: std::sync::Arc<dyn QuorumPolicy>,
+    /// Loopback: also deliver our own broadcasts to ourselves. A Candidate
+    /// then votes for itself by handling its own Propose like any Follower
+    /// would, rather than recording the vote directly.
+    pub include_self: bool,
+    /// Value this node proposes at startup, making it the initial candidate
+    pub proposal: Option<V>,
+    /// Node that bootstraps the protocol when nobody has an explicit
+    /// `proposal`: it proposes `fallback` at startup. `new` picks the lowest
+    /// peer id so a plain cluster isn't inert.
+    pub leader_id: Option<Id>,
+    /// What the bootstrap leader, or a node whose election timer fires,
+    /// proposes without a value of its own; `Value::V0` from `new`
+    pub fallback: Option<V>,
+    /// Crashed nodes never send or react to anything
+    pub crashed: bool,
+    /// Peers on our side of a network partition; `None` means fully connected
+    pub reachable: Option<Vec<Id>>,
+    /// Byzantine nodes equivocate: they vote for every proposal they see,
+    /// including ones competing with their own. The only Byzantine
+    /// behaviour; [`byzantine::ByzantineActor`] builds its lies on top of it.
+    pub byzantine: bool,
+    /// Recovering nodes ask their peers for the decision on startup
+    pub recovering: bool,
+    /// Followers start an election timer and campaign in a new term if no
+    /// proposal shows up before it fires
+    pub election_timeout: bool,
+    /// How many log slots to decide in a row; a plain single decision is 1
+    pub slots: u64,
+    /// Leaders keep re-sending their Commit until every peer they can reach
+    /// acknowledges it, and everyone acknowledges the Commits they get. Each retry can add
+    /// another copy to the network, so checking a lossy model with this on
+    /// needs a depth bound.
+    pub commit_retry: bool,
+    /// Everyone acknowledges the Commits they get, and Leaders count the
+    /// acknowledgements in `commit_acks`, but without `commit_retry`'s
+    /// re-sending. Implied by `commit_retry`.
+    pub ack_commits: bool,
+    /// Split deciding into two phases: a leader with a quorum of votes first
+    /// has a quorum lock the value with Prepare/Promise, and only then
+    /// commits. Locked nodes never vote for another value, so a later term
+    /// can't gather a quorum for something else.
+    pub two_phase: bool,
+    /// Stake per node, indexed like `peer_ids`. Quorums are judged on the
+    /// voters' total stake out of the cluster's rather than on head counts.
+    weights: Vec<u64>,
+    /// Values this node is willing to vote for; a Follower ignores any
+    /// other proposal. `None` accepts everything.
+    pub valid_values: Option<BTreeSet<V>>,
+    /// Learners only listen for Commits. Voters should give them no stake
+    /// (see `with_learners`) so quorums don't wait on them.
+    pub learner: bool,
+    /// A Candidate that sees a Propose for a larger value (by `Ord`) in its
+    /// term drops its own bid and votes for that one instead, so a split
+    /// where every node proposes something different still converges.
+    /// Candidates only ever switch upwards, so within a term that happens
+    /// at most once per value.
+    pub tie_break: bool,
+    /// A Candidate arms its election timer and, once its bid can't reach a
+    /// quorum even if every peer it hasn't heard from votes for it, counts
+    /// each timeout as a stall; this many stalls and it turns Aborted. Only
+    /// rival campaigns rule peers out, so this never fires while a quorum is
+    /// still possible. `None` never aborts.
+    pub abort_after: Option<u32>,
+    /// Stands in for a client: at startup we send ourselves a
+    /// `ClientRequest` for this value instead of campaigning straight away,
+    /// so the campaign only starts once the request is delivered
+    pub client_request: Option<V>,
+    /// Once decided, answer any round traffic from a peer that's still
+    /// undecided with a Commit for our decision, so a node whose Commit was
+    /// lost can still learn the outcome
+    pub catch_up: bool,
+    /// On first deciding from someone else's Commit, pass that Commit on to
+    /// every other peer, once. Peers that already decided ignore it rather
+    /// than gossip again, so dissemination dies out by itself.
+    pub gossip: bool,
+    /// Decide only once Commits for the same value have come in from a
+    /// quorum of distinct senders, instead of on the first Commit. A node
+    /// that voted for (or proposed) the value passes the Commit on to its
+    /// peers, once, as its own; a lone forged Commit for a value nobody
+    /// voted for then never gathers a quorum.
+    pub commit_quorum: bool,
+    /// Chaos for [`simulate`]: the chance this node, having accepted a
+    /// proposal, never gets its Vote out, as a slow or flaky node would.
+    /// `simulate` rolls for it with an RNG seeded from the run's seed; the
+    /// exhaustive checker ignores it, so its results stay deterministic.
+    pub drop_vote_prob: f64,
 }
 
 impl ConsensusActor {
     pub fn new(peer_ids: Vec<Id>) -> Self {
-        let quorum_size = (peer_ids.len() / 2) + 1;
+        Self::with_peers(peer_ids).with_fallback(Value::V0)
+    }
+
+    /// A passive node that records the decision from the first Commit it
+    /// sees and otherwise stays silent. `peer_ids` is the whole cluster;
+    /// give it the voters' `with_learners` too, or it judges the Commit's
+    /// certificate by a quorum of the whole cluster.
+    pub fn learner(peer_ids: Vec<Id>) -> Self {
         ConsensusActor {
-            peer_ids,
-            quorum_size,
+            learner: true,
+            ..Self::new(peer_ids).with_leader(None)
         }
     }
 
-    fn has_quorum(&self, votes: &HashSet<Id>) -> bool {
-        // Fixed: was using >= peer_ids.len() / 2, but quorum needs majority (n/2 + 1)
-        votes.len() >= self.quorum_size
+    /// A cluster that tolerates `f` Byzantine nodes. That takes n >= 3f + 1
+    /// nodes, with quorums of [`byzantine_quorum_bound`], which is 2f + 1 at
+    /// n = 3f + 1: any two quorums then share at least one honest node, and
+    /// the n - f honest nodes can still form a quorum on their own.
+    ///
+    /// Panics if there are fewer than 3f + 1 peers.
+    pub fn with_byzantine_tolerance(peer_ids: Vec<Id>, f: usize) -> Self {
+        let n = peer_ids.len();
+        assert!(
+            n > 3 * f,
+            "tolerating {} Byzantine nodes takes at least 3f + 1 = {} nodes, got {}",
+            f,
+            3 * f + 1,
+            n
+        );
+        Self::new(peer_ids).with_quorum_policy(Threshold(byzantine_quorum_bound(n, f)))
     }
+}
 
-    fn broadcast(&self, my_id: Id, msg: ConsensusMsg, out: &mut Out<Self>) {
-        // broadcast to everyone except ourselves
-        for &peer in &self.peer_ids {
-            if peer != my_id {
-                out.send(peer, msg.clone());
-            }
+impl<V: ProposalValue> ConsensusActor<V> {
+    /// Same as [`ConsensusActor::new`] for any value type, without a
+    /// `fallback`: only nodes given a proposal start anything
+    pub fn with_peers(peer_ids: Vec<Id>) -> Self {
+        let leader_id = peer_ids.iter().min().copied();
+        let peer_count = peer_ids.len();
+        ConsensusActor {
+            peer_ids,
+            quorum_policy: std::sync::Arc::new(SimpleMajority),
+            include_self: false,
+            proposal: None,
+            leader_id,
+            fallback: None,
+            crashed: false,
+            reachable: None,
+            byzantine: false,
+            recovering: false,
+            election_timeout: false,
+            slots: 1,
+            commit_retry: false,
+            ack_commits: false,
+            two_phase: false,
+            weights: vec![1; peer_count],
+            valid_values: None,
+            learner: false,
+            tie_break: false,
+            abort_after: None,
+            client_request: None,
+            catch_up: false,
+            gossip: false,
+            commit_quorum: false,
+            drop_vote_prob: 0.0,
         }
     }
-}
 
-impl Hash for ConsensusActor {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.peer_ids.hash(state);
-        self.quorum_size.hash(state);
+    pub fn with_proposal(mut self, value: V) -> Self {
+        self.proposal = Some(value);
+        self
     }
-}
 
-impl PartialEq for ConsensusActor {
-    fn eq(&self, other: &Self) -> bool {
-        self.peer_ids == other.peer_ids && self.quorum_size == other.quorum_size
+    pub fn with_leader(mut self, leader_id: Option<Id>) -> Self {
+        self.leader_id = leader_id;
+        self
     }
-}
 
-impl Eq for ConsensusActor {}
+    pub fn with_fallback(mut self, value: V) -> Self {
+        self.fallback = Some(value);
+        self
+    }
 
-impl Actor for ConsensusActor {
-    type Msg = ConsensusMsg;
-    type State = ConsensusState;
-    type Timer = ();
+    /// What node `id` campaigns for, if anything: at startup, or once its
+    /// client request arrives
+    pub fn initial_proposal(&self, id: Id) -> Option<V> {
+        self.client_request
+            .or(self.proposal)
+            .or(self.fallback.filter(|_| self.leader_id == Some(id)))
+    }
 
-    fn on_start(&self, _id: Id, _o: &mut Out<Self>) -> Self::State {
-        ConsensusState {
-            role: NodeRole::Follower,
-            proposed_value: None,
-            votes_received: HashSet::new(),
-            decided_value: None,
-        }
+    pub fn with_crashed(mut self, crashed: bool) -> Self {
+        self.crashed = crashed;
+        self
     }
 
-    fn on_msg(
-        &self,
-        id: Id,
-        state: &mut Cow<Self::State>,
-        src: Id,
-        msg: Self::Msg,
-        o: &mut Out<Self>,
-    ) {
-        match msg {
-            ConsensusMsg::Propose { value } => {
-                // Follower receives a proposal
-                if state.role == NodeRole::Follower && state.proposed_value.is_none() {
-                    let state = state.to_mut();
-                    state.proposed_value = Some(value);
-                    // Vote for the proposal
-                    o.send(src, ConsensusMsg::Vote { value });
-                }
-            }
+    pub fn with_reachable(mut self, reachable: Vec<Id>) -> Self {
+        self.reachable = Some(reachable);
+        self
+    }
 
-            ConsensusMsg::Vote { value } => {
-                // Candidate collects votes
-                if state.role == NodeRole::Candidate {
-                    if state.proposed_value == Some(value) {
-                        let state = state.to_mut();
-                        state.votes_received.insert(src);
-
-                        // Check if we have quorum (majority of nodes)
-                        // TODO: what if we get votes for different values? ignore them for now
-                        if self.has_quorum(&state.votes_received) {
-                            state.role = NodeRole::Leader;
-                            // Broadcast commit - this is the "prepare" phase basically
-                            self.broadcast(id, ConsensusMsg::Commit { value }, o);
-                        }
-                    }
-                }
-            }
+    pub fn with_byzantine(mut self, byzantine: bool) -> Self {
+        self.byzantine = byzantine;
+        self
+    }
 
-            ConsensusMsg::Commit { value } => {
-                // Any node can receive commit and decide
-                if state.decided_value.is_none() {
-                    let state = state.to_mut();
-                    state.decided_value = Some(value);
-                    state.role = NodeRole::Decided;
-                }
+    pub fn with_recovering(mut self, recovering: bool) -> Self {
+        self.recovering = recovering;
+        self
+    }
+
+    pub fn with_include_self(mut self, include_self: bool) -> Self {
+        self.include_self = include_self;
+        self
+    }
+
+    pub fn with_election_timeout(mut self, election_timeout: bool) -> Self {
+        self.election_timeout = election_timeout;
+        self
+    }
+
+    pub fn with_slots(mut self, slots: u64) -> Self {
+        self.slots = slots;
+        self
+    }
+
+    pub fn with_commit_retry(mut self, commit_retry: bool) -> Self {
+        self.commit_retry = commit_retry;
+        self
+    }
+
+    pub fn with_commit_acks(mut self, ack_commits: bool) -> Self {
+        self.ack_commits = ack_commits;
+        self
+    }
+
+    fn acks_commits(&self) -> bool {
+        self.ack_commits || self.commit_retry
+    }
+
+    pub fn with_tie_break(mut self, tie_break: bool) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn with_client_request(mut self, value: V) -> Self {
+        self.client_request = Some(value);
+        self
+    }
+
+    pub fn with_catch_up(mut self, catch_up: bool) -> Self {
+        self.catch_up = catch_up;
+        self
+    }
+
+    pub fn with_gossip(mut self, gossip: bool) -> Self {
+        self.gossip = gossip;
+        self
+    }
+
+    pub fn with_commit_quorum(mut self, commit_quorum: bool) -> Self {
+        self.commit_quorum = commit_quorum;
+        self
+    }
+
+    /// Panics unless `p` is a probability
+    pub fn with_drop_vote_prob(mut self, p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "drop_vote_prob must be between 0 and 1, got {}", p);
+        self.drop_vote_prob = p;
+        self
+    }
+
+    pub fn with_abort_after(mut self, stalls: u32) -> Self {
+        self.abort_after = Some(stalls);
+        self
+    }
+
+    pub fn with_two_phase(mut self, two_phase: bool) -> Self {
+        self.two_phase = two_phase;
+        self
+    }
+
+    pub fn with_weights(mut self, weights: Vec<u64>) -> Self {
+        assert_eq!(weights.len(), self.peer_ids.len(), "one weight per peer");
+        self.weights = weights;
+        self
+    }
+
+    pub fn with_valid_values(mut self, values: impl IntoIterator<Item = V>) -> Self {
+        self.valid_values = Some(values.into_iter().collect());
+        self
+    }
+
+    /// Whether we'd vote for `value` at all
+    fn is_valid(&self, value: &V) -> bool {
+        self.valid_values.as_ref().is_none_or(|valid| valid.contains(value))
+    }
+
+    /// Zero the stake of `learners` so they're told about decisions without
+    /// counting towards any quorum
+    pub fn with_learners(mut self, learners: impl IntoIterator<Item = Id>) -> Self {
+        for learner in learners {
+            if let Some(i) = self.peer_ids.iter().position(|&p| p == learner) {
+                self.weights[i] = 0;
             }
         }
+        self
     }
 
-    // NOTE: Removed on_random - not part of this Stateright version's Actor trait
-    // The API changed and on_start only takes 3 params now, not 4
-}
-
-// Helper functions for checking properties
-// These get used by the model checker in main.rs
+    pub fn with_quorum_policy(mut self, policy: impl QuorumPolicy + 'static) -> Self {
+        self.quorum_policy = std::sync::Arc::new(policy);
+        self
+    }
 
-pub fn check_agreement(states: &[std::sync::Arc<ConsensusState>]) -> bool {
-    // Agreement: all nodes that decide must decide the same value
-    let decided: Vec<Value> = states
-        .iter()
-        .filter_map(|s| s.decided_value)
-        .collect();
+    /// Everyone in the cluster as we see it, ourselves included
+    pub fn peers(&self) -> &[Id] {
+        &self.peer_ids
+    }
 
-    if decided.len() < 2 {
-        return true; // trivially true if 0 or 1 node decided
+    /// Fewest votes the policy accepts for this cluster, or one more than
+    /// the cluster size if it accepts none. Counted in stake, which is the
+    /// same as votes with the default unit weights.
+    pub fn quorum_size(&self) -> usize {
+        let total = self.total_weight();
+        (0..=total)
+            .find(|&votes| self.quorum_policy.is_quorum(votes, total))
+            .unwrap_or(total + 1)
     }
 
-    let first = decided[0];
-    decided.iter().all(|&v| v == first)
-}
+    /// [`min_faulty_to_break_agreement`] by our own count: our membership,
+    /// stake and policy. Faults take the heaviest peers first, as that's the
+    /// fewest nodes it takes to leave the rest short of a quorum.
+    pub fn min_faulty_to_break_agreement(&self) -> usize {
+        let quorum = self.quorum_size() as u64;
+        let mut weights = self.weights.clone();
+        weights.sort_unstable_by(|a, b| b.cmp(a));
+        let mut honest = self.total_weight() as u64;
+        let mut faulty = 0;
+        for weight in weights {
+            if honest < quorum {
+                break;
+            }
+            honest -= weight;
+            faulty += 1;
+        }
+        faulty
+    }
 
-pub fn check_validity(states: &[std::sync::Arc<ConsensusState>]) -> bool {
-    states
-        .iter()
-        .all(|s| s.decided_value.is_none() || matches!(s.decided_value, Some(Value::V0 | Value::V1 | Value::V2)))
-}
+    /// Joins `id` to the cluster with unit stake, unless it's a member
+    /// already. Quorums are derived from the current membership on every
+    /// check, so they grow with it.
+    pub fn add_peer(&mut self, id: Id) {
+        if self.peer_ids.contains(&id) {
+            return;
+        }
+        self.peer_ids.push(id);
+        self.weights.push(1);
+    }
 
-pub fn has_decision(states: &[std::sync::Arc<ConsensusState>]) -> bool {
-    // Check if at least one node has decided
-    states.iter().any(|s| s.decided_value.is_some())
-}
+    /// Drops `id` and its stake from the cluster, if it's a member
+    pub fn remove_peer(&mut self, id: Id) {
+        if let Some(i) = self.peer_ids.iter().position(|&p| p == id) {
+            self.peer_ids.remove(i);
+            self.weights.remove(i);
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use stateright::actor::{ActorModel, Network};
-    use stateright::{Checker, Expectation, Model};
+    /// Stake of the peer at `index`
+    fn weight(&self, index: usize) -> u64 {
+        self.weights[index]
+    }
 
-    #[test]
-    fn test_three_node_consensus() {
-        // Test with 3 nodes - simplest case
-        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
-        
-        let model = ActorModel::new((), ())
-            .actor(ConsensusActor::new(peer_ids.clone()))
-            .actor(ConsensusActor::new(peer_ids.clone()))
-            .actor(ConsensusActor::new(peer_ids.clone()))
-            .init_network(Network::new_unordered_nonduplicating([]))
-            .property(
-                Expectation::Always,
-                "agreement",
-                |_, state| check_agreement(&state.actor_states)
-            )
-            .property(
-                Expectation::Always,
-                "validity",
-                |_, state| check_validity(&state.actor_states)
-            );
+    fn total_weight(&self) -> usize {
+        (0..self.peer_ids.len()).map(|i| self.weight(i)).sum::<u64>() as usize
+    }
 
-        let result = model.checker().threads(1).spawn_bfs().join();
-        
-        // Check that no property violations were found
-        assert!(result.discovery("agreement").is_none(), "Agreement property violated");
-        assert!(result.discovery("validity").is_none(), "Validity property violated");
-        assert!(result.unique_state_count() > 0, "Should explore at least some states");
+    /// Whether `voters` hold enough stake between them; ids that aren't
+    /// our peers count for nothing
+    fn has_quorum<'a>(&self, voters: impl IntoIterator<Item = &'a Id>) -> bool {
+        let stake: u64 = voters
+            .into_iter()
+            .filter_map(|voter| self.peer_ids.iter().position(|p| p == voter))
+            .map(|i| self.weight(i))
+            .sum();
+        debug_assert!(
+            self.quorum_size() <= self.total_weight(),
+            "quorum {} can never be reached with {} stake in the cluster",
+            self.quorum_size(),
+            self.total_weight()
+        );
+        self.quorum_policy.is_quorum(stake as usize, self.total_weight())
     }
 
-    #[test]
-    fn test_consensus_state_equality() {
-        let state1 = ConsensusState {
-            role: NodeRole::Follower,
-            proposed_value: Some(Value::V0),
-            votes_received: HashSet::new(),
-            decided_value: None,
+    /// Checks that no two disjoint groups of peers both hold a quorum's worth
+    /// of stake. Unlike [`QuorumPolicy::validate`] this accounts for
+    /// `weights`, e.g. two heavy nodes can each be a quorum on their own.
+    /// Two quorums always overlap once a quorum is more than half the total
+    /// stake, since disjoint ones would need twice that between them. Below
+    /// that it's rejected, naming two disjoint quorums when the heaviest
+    /// peers make one and the rest another. A quorum bigger than the whole
+    /// cluster is rejected first, as nothing could ever be decided.
+    pub fn validate_quorums(&self) -> Result<(), String> {
+        let (quorum, total) = (self.quorum_size(), self.total_weight());
+        if quorum > total {
+            return Err(format!(
+                "quorum {} exceeds the cluster's total stake {}, so no value can ever be decided",
+                quorum, total
+            ));
+        }
+        if 2 * quorum > total {
+            return Ok(());
+        }
+        let mut heaviest: Vec<usize> = (0..self.peer_ids.len()).collect();
+        heaviest.sort_by_key(|&i| std::cmp::Reverse(self.weight(i)));
+        let mut stake = 0;
+        let cut = heaviest
+            .iter()
+            .position(|&i| {
+                stake += self.weight(i) as usize;
+                stake >= quorum
+            })
+            .map_or(heaviest.len(), |last| last + 1);
+        let group = |indices: &[usize]| -> Vec<Id> {
+            let mut ids: Vec<Id> = indices.iter().map(|&i| self.peer_ids[i]).collect();
+            ids.sort();
+            ids
         };
+        let (one, other) = (group(&heaviest[..cut]), group(&heaviest[cut..]));
+        if self.has_quorum(&one) && self.has_quorum(&other) {
+            let list = |ids: &[Id]| ids.iter().map(|&id| usize::from(id).to_string()).collect::<Vec<_>>().join(", ");
+            return Err(format!("{{{}}} and {{{}}} are disjoint quorums", list(&one), list(&other)));
+        }
+        Err(format!("quorum {} is at most half the cluster's total stake {}, so two quorums can be disjoint", quorum, total))
+    }
 
-        let mut state2 = ConsensusState {
-            role: NodeRole::Follower,
-            proposed_value: Some(Value::V0),
-            votes_received: HashSet::new(),
-            decided_value: None,
+    /// Whether our bid is out of reach of a quorum in this term: the voters
+    /// we have plus every peer we haven't heard from fall short
+    fn bid_is_dead(&self, state: &ConsensusState<V>) -> bool {
+        let Some(mine) = state.proposed_value else {
+            return false;
         };
+        let heard: BTreeSet<Id> = state.votes_received.values().flatten().chain(&state.rivals).copied().collect();
+        let voters = state.votes_received.get(&mine).into_iter().flatten();
+        !self.has_quorum(self.peer_ids.iter().filter(|p| !heard.contains(p)).chain(voters))
+    }
 
-        assert_eq!(state1, state2);
+    /// Whether `certificate` names only peers we know and, between them, a
+    /// quorum. It's just a set of ids, so this stops malformed or
+    /// undersized claims, not a liar willing to name a full quorum.
+    fn certified(&self, certificate: &BTreeSet<Id>) -> bool {
+        certificate.iter().all(|voter| self.peer_ids.contains(voter)) && self.has_quorum(certificate)
+    }
 
-        state2.role = NodeRole::Candidate;
-        assert_ne!(state1, state2);
+    /// Whether `value` on its own has gathered a quorum
+    fn has_quorum_for(&self, votes: &HashMap<V, HashSet<Id>>, value: V) -> bool {
+        votes.get(&value).is_some_and(|voters| self.has_quorum(voters))
     }
 
-    #[test]
-    fn test_quorum_calculation() {
-        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
-        let actor = ConsensusActor::new(peer_ids);
-        
-        assert_eq!(actor.quorum_size, 2, "Quorum for 3 nodes should be 2");
+    /// Become a Candidate for `value` in the current term: vote for it
+    /// ourselves and ask everyone else
+    fn campaign(&self, my_id: Id, state: &mut ConsensusState<V>, value: V, out: &mut impl Outbox<V>) {
+        if state.has_proposed_in_term {
+            log::trace!("node {}: already proposed in term {}, not proposing {:?}", usize::from(my_id), state.term, value);
+            return;
+        }
+        state.has_proposed_in_term = true;
+        state.role = NodeRole::Candidate;
+        state.proposed_value = Some(value);
+        if self.abort_after.is_some() {
+            out.set_timer(Timer::Election);
+        }
+        self.broadcast(my_id, ConsensusMsg::Propose { value, term: state.term, slot: state.slot }, out);
+        if self.include_self {
+            // Our own Propose is on its way back to us
+            return;
+        }
+        // The proposer implicitly votes for its own value
+        state.votes_received.entry(value).or_default().insert(my_id);
+        state.vote_log.push((my_id, value));
+        state.vote_terms.insert((my_id, value, state.term));
 
-        let mut votes = HashSet::new();
-        assert!(!actor.has_quorum(&votes), "Empty votes shouldn't be quorum");
+        // A quorum of one is already complete
+        if self.has_quorum_for(&state.votes_received, value) {
+            state.role = NodeRole::Leader;
+            self.lead(my_id, state, value, out);
+        }
+    }
 
-        votes.insert(Id::from(0));
-        assert!(!actor.has_quorum(&votes), "1 vote isn't quorum for 3 nodes");
+    /// A fresh Leader for `value`: commit straight away, or with two phases
+    /// lock it ourselves and ask everyone else to do the same
+    fn lead(&self, my_id: Id, state: &mut ConsensusState<V>, value: V, out: &mut impl Outbox<V>) {
+        if !self.two_phase {
+            self.commit(my_id, state, value, out);
+            return;
+        }
+        state.locked = Some(value);
+        state.promises = BTreeSet::from([my_id]);
+        let certificate = state.votes_received.get(&value).into_iter().flatten().copied().collect();
+        self.broadcast(my_id, ConsensusMsg::Prepare { value, term: state.term, slot: state.slot, certificate }, out);
+        if self.has_quorum(&state.promises) {
+            self.commit(my_id, state, value, out);
+        }
+    }
 
-        votes.insert(Id::from(1));
-        assert!(actor.has_quorum(&votes), "2 votes should be quorum for 3 nodes");
+    /// Leader announces the decision to everyone and records it itself
+    /// rather than sending itself a Commit, moving on to Decided like the rest
+    fn commit(&self, my_id: Id, state: &mut ConsensusState<V>, value: V, out: &mut impl Outbox<V>) {
+        let certificate: BTreeSet<Id> = if self.two_phase {
+            state.promises.clone()
+        } else {
+            state.votes_received.get(&value).into_iter().flatten().copied().collect()
+        };
+        let msg = ConsensusMsg::Commit { value, term: state.term, slot: state.slot, certificate: certificate.clone() };
+        if self.acks_commits() {
+            state.commit_acks = BTreeSet::from([my_id]);
+        }
+        if self.commit_retry {
+            state.pending_commit = Some(msg.clone());
+            out.set_timer(Timer::CommitRetry);
+        }
+        self.broadcast(my_id, msg, out);
+        self.decide(my_id, state, value, certificate, out);
     }
 
-    #[test]
-    fn test_agreement_property() {
-        // Test agreement checker with same decisions
-        let states: Vec<std::sync::Arc<ConsensusState>> = vec![
+    /// Record the decision for the current slot and, if there are slots
+    /// left, start over on the next one; the original proposer campaigns again
+    fn decide(&self, my_id: Id, state: &mut ConsensusState<V>, value: V, certificate: BTreeSet<Id>, out: &mut impl Outbox<V>) {
+        state.decided_log.push(value);
+        if state.slot + 1 < self.slots {
+            state.slot += 1;
+            state.role = NodeRole::Follower;
+            state.proposed_value = None;
+            state.votes_received.clear();
+            state.vote_log.clear();
+            state.vote_terms.clear();
+            state.certificate.clear();
+            // Retries and locks only cover the slot being decided
+            state.pending_commit = None;
+            state.commit_acks.clear();
+            state.locked = None;
+            state.promises.clear();
+            state.rivals.clear();
+            state.stalls = 0;
+            state.has_proposed_in_term = false;
+            state.commits_received.clear();
+            if let Some(value) = self.initial_proposal(my_id) {
+                self.campaign(my_id, state, value, out);
+            }
+        } else {
+            state.decided_value = Some(value);
+            state.role = NodeRole::Decided;
+            state.certificate = certificate;
+        }
+    }
+
+    /// Counts `src`'s Commit for `value` and, if it's the value we voted
+    /// for, passes `commit` on as our own the first time. Whether the
+    /// senders now make a quorum.
+    fn tally_commit(&self, id: Id, state: &mut Cow<ConsensusState<V>>, src: Id, value: V, commit: ConsensusMsg<V>, out: &mut impl Outbox<V>) -> bool {
+        if !state.commits_received.get(&value).is_some_and(|senders| senders.contains(&src)) {
+            state.to_mut().commits_received.entry(value).or_default().insert(src);
+        }
+        let echoed = state.commits_received[&value].contains(&id);
+        if state.proposed_value == Some(value) && !echoed {
+            state.to_mut().commits_received.entry(value).or_default().insert(id);
+            self.broadcast(id, commit, out);
+        }
+        self.has_quorum(&state.commits_received[&value])
+    }
+
+    /// Flags a Commit for `value` reaching a node that decided something
+    /// else. The decision stands; the flag is there for NoSafetyTrap.
+    fn trap_conflicting_commit(id: Id, state: &mut Cow<ConsensusState<V>>, src: Id, value: V) {
+        if state.decided_value == Some(value) || state.safety_violation {
+            return;
+        }
+        log::warn!(
+            "node {}: commit for {:?} from {} conflicts with our decision {:?}",
+            usize::from(id),
+            value,
+            usize::from(src),
+            state.decided_value
+        );
+        state.to_mut().safety_violation = true;
+    }
+
+    /// Every broadcast goes through [`ConsensusActor::broadcast_ordered`], so
+    /// sends always come out in ascending id order
+    fn broadcast(&self, my_id: Id, msg: ConsensusMsg<V>, out: &mut impl Outbox<V>) {
+        self.broadcast_ordered(my_id, msg, out);
+    }
+
+    /// Sends `msg` to every peer we can reach, except ourselves unless
+    /// loopback is on, in ascending id order whatever order `peer_ids` lists
+    /// them in. The checker doesn't care about the order, but exported
+    /// traces of the same run then always line up for diffing.
+    pub fn broadcast_ordered(&self, my_id: Id, msg: ConsensusMsg<V>, out: &mut impl Outbox<V>) {
+        let mut peers: Vec<Id> = self
+            .peer_ids
+            .iter()
+            .copied()
+            .filter(|&peer| peer != my_id || self.include_self)
+            .filter(|peer| self.reaches(peer))
+            .collect();
+        peers.sort();
+        for peer in peers {
+            out.send(peer, msg.clone());
+        }
+    }
+
+    /// Whether our link to `peer` is up; always, unless `reachable` says otherwise
+    fn reaches(&self, peer: &Id) -> bool {
+        self.reachable.as_ref().is_none_or(|r| r.contains(peer))
+    }
+}
+
+impl<V: ProposalValue> Hash for ConsensusActor<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.peer_ids.hash(state);
+        // Policies that agree on this cluster's threshold behave identically
+        self.quorum_size().hash(state);
+        self.include_self.hash(state);
+        self.proposal.hash(state);
+        self.leader_id.hash(state);
+        self.fallback.hash(state);
+        self.crashed.hash(state);
+        self.reachable.hash(state);
+        self.byzantine.hash(state);
+        self.recovering.hash(state);
+        self.election_timeout.hash(state);
+        self.slots.hash(state);
+        self.commit_retry.hash(state);
+        self.ack_commits.hash(state);
+        self.two_phase.hash(state);
+        self.weights.hash(state);
+        self.valid_values.hash(state);
+        self.learner.hash(state);
+        self.tie_break.hash(state);
+        self.abort_after.hash(state);
+        self.client_request.hash(state);
+        self.catch_up.hash(state);
+        self.gossip.hash(state);
+        self.commit_quorum.hash(state);
+        // drop_vote_prob left out: only simulate reads it, and to the
+        // checker a chaotic node is like any other
+    }
+}
+
+impl<V: ProposalValue> PartialEq for ConsensusActor<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.peer_ids == other.peer_ids
+            && self.quorum_size() == other.quorum_size()
+            && self.include_self == other.include_self
+            && self.proposal == other.proposal
+            && self.leader_id == other.leader_id
+            && self.fallback == other.fallback
+            && self.crashed == other.crashed
+            && self.reachable == other.reachable
+            && self.byzantine == other.byzantine
+            && self.recovering == other.recovering
+            && self.election_timeout == other.election_timeout
+            && self.slots == other.slots
+            && self.commit_retry == other.commit_retry
+            && self.ack_commits == other.ack_commits
+            && self.two_phase == other.two_phase
+            && self.weights == other.weights
+            && self.valid_values == other.valid_values
+            && self.learner == other.learner
+            && self.tie_break == other.tie_break
+            && self.abort_after == other.abort_after
+            && self.client_request == other.client_request
+            && self.catch_up == other.catch_up
+            && self.gossip == other.gossip
+            && self.commit_quorum == other.commit_quorum
+        // drop_vote_prob left out, as from the hash
+    }
+}
+
+impl<V: ProposalValue> Eq for ConsensusActor<V> {}
+
+/// Lets the handlers write straight into stateright's Out, with the model's
+/// usual timeout for every timer
+impl<V: ProposalValue> Outbox<V> for Out<ConsensusActor<V>> {
+    fn send(&mut self, dst: Id, msg: ConsensusMsg<V>) {
+        Out::send(self, dst, msg);
+    }
+
+    fn set_timer(&mut self, timer: Timer) {
+        Out::set_timer(self, timer, model_timeout());
+    }
+}
+
+impl<V: ProposalValue> Actor for ConsensusActor<V> {
+    type Msg = ConsensusMsg<V>;
+    type State = ConsensusState<V>;
+    type Timer = Timer;
+
+    fn on_start(&self, id: Id, o: &mut Out<Self>) -> Self::State {
+        let mut state = ConsensusState {
+            role: NodeRole::Follower,
+            proposed_value: None,
+            votes_received: HashMap::new(),
+            decided_value: None,
+            term: 0,
+            vote_terms: BTreeSet::new(),
+            certificate: BTreeSet::new(),
+            slot: 0,
+            decided_log: Vec::new(),
+            pending_commit: None,
+            commit_acks: BTreeSet::new(),
+            locked: None,
+            promises: BTreeSet::new(),
+            rivals: BTreeSet::new(),
+            stalls: 0,
+            has_proposed_in_term: false,
+            commits_received: HashMap::new(),
+            safety_violation: false,
+            deferred: BTreeSet::new(),
+            vote_log: Vec::new(),
+            rejected: 0,
+        };
+
+        if self.learner {
+            state.role = NodeRole::Learner;
+            return state;
+        }
+
+        if self.recovering && !self.crashed {
+            self.broadcast(id, ConsensusMsg::StateRequest, o);
+        }
+
+        match (self.initial_proposal(id), self.crashed) {
+            (_, true) => {}
+            (Some(value), false) if self.client_request.is_some() => o.send(id, ConsensusMsg::ClientRequest { value }),
+            (Some(value), false) => self.campaign(id, &mut state, value, o),
+            (None, false) if self.election_timeout => Outbox::set_timer(o, Timer::Election),
+            (None, false) => {}
+        }
+        state
+    }
+
+    fn on_timeout(&self, id: Id, state: &mut Cow<Self::State>, timer: &Self::Timer, o: &mut Out<Self>) {
+        if self.crashed {
+            return;
+        }
+
+        match timer {
+            Timer::Election => {
+                // Nobody proposed to us, so whoever should have is presumably
+                // dead. We run with whatever we're locked on, else our own value.
+                let bid = state.locked.or(self.proposal).or(self.fallback);
+                if let (true, Some(value)) =
+                    (self.election_timeout && state.role == NodeRole::Follower && state.proposed_value.is_none(), bid)
+                {
+                    let state = state.to_mut();
+                    state.term += 1;
+                    state.has_proposed_in_term = false;
+                    state.votes_received.clear();
+                    state.vote_log.clear();
+                    self.campaign(id, state, value, o);
+                } else if let Some(limit) = self.abort_after.filter(|_| state.role == NodeRole::Candidate) {
+                    let stalled_out = self.bid_is_dead(state) && {
+                        let state = state.to_mut();
+                        state.stalls += 1;
+                        state.stalls >= limit
+                    };
+                    if stalled_out {
+                        log::debug!("node {}: no quorum left for {:?}, aborting", usize::from(id), state.proposed_value);
+                        state.to_mut().role = NodeRole::Aborted;
+                    } else {
+                        Outbox::set_timer(o, Timer::Election);
+                    }
+                }
+            }
+            Timer::CommitRetry => {
+                // Cleared once every reachable peer acknowledged, which also ends the retries
+                if let Some(msg) = &state.pending_commit {
+                    for &peer in &self.peer_ids {
+                        if !state.commit_acks.contains(&peer) && self.reaches(&peer) {
+                            o.send(peer, msg.clone());
+                        }
+                    }
+                    Outbox::set_timer(o, Timer::CommitRetry);
+                }
+            }
+        }
+    }
+
+    fn on_msg(
+        &self,
+        id: Id,
+        state: &mut Cow<Self::State>,
+        src: Id,
+        msg: Self::Msg,
+        o: &mut Out<Self>,
+    ) {
+        self.transition(id, state, src, msg, o);
+    }
+
+    // NOTE: Removed on_random - not part of this Stateright version's Actor trait
+    // The API changed and on_start only takes 3 params now, not 4
+}
+
+impl<V: ProposalValue> ConsensusActor<V> {
+    /// Everything `on_msg` does for a live node. A message that changes
+    /// nothing and sends nothing was rejected: stale, out of context, or
+    /// for a role we're not in.
+    ///
+    /// Decision points are logged under `debug` and ignored messages under
+    /// `trace`. Logging has no effect on the states, and costs nothing
+    /// unless a logger is installed, as `simulate --verbose` does.
+    fn handle_msg(&self, id: Id, state: &mut Cow<ConsensusState<V>>, src: Id, msg: ConsensusMsg<V>, o: &mut impl Outbox<V>) {
+        let (me, from) = (usize::from(id), usize::from(src));
+        // Only the current slot's round is ours to act on. Later slots'
+        // messages wait in `deferred` until we've caught up to them.
+        if state.role == NodeRole::Aborted {
+            log::trace!("node {}: ignoring {:?} from {}: we've aborted", me, msg, from);
+            return;
+        }
+        match msg.slot() {
+            Some(slot) if slot > state.slot => {
+                log::trace!("node {}: holding {:?} from {} until we reach slot {}", me, msg, from, slot);
+                if !state.deferred.contains(&(src, msg.clone())) {
+                    state.to_mut().deferred.insert((src, msg));
+                }
+                return;
+            }
+            Some(slot) if slot < state.slot => {
+                log::trace!("node {}: ignoring {:?} from {}: we're on slot {}", me, msg, from, state.slot);
+                return;
+            }
+            _ => {}
+        }
+
+        if self.learner {
+            if let ConsensusMsg::Commit { value, certificate, .. } = msg {
+                if !self.certified(&certificate) {
+                    log::trace!("node {}: ignoring commit from {}: {:?} is no quorum", me, from, certificate);
+                } else if state.decided_value.is_none() {
+                    log::debug!("node {}: learned {:?} from {}", me, value, from);
+                    let state = state.to_mut();
+                    self.decide(id, state, value, certificate, o);
+                    state.role = NodeRole::Learner;
+                } else {
+                    Self::trap_conflicting_commit(id, state, src, value);
+                }
+            }
+            return;
+        }
+
+        if self.catch_up && src != id {
+            // Only an undecided node campaigns, votes or locks, so one still
+            // doing so missed the Commit. Once our reply lands it goes quiet,
+            // which keeps this from turning into endless chatter.
+            let behind = matches!(
+                msg,
+                ConsensusMsg::Propose { .. } | ConsensusMsg::Vote { .. } | ConsensusMsg::Prepare { .. } | ConsensusMsg::Promise { .. }
+            );
+            if let (Some(value), Some(term), true) = (state.decided_value, msg.term(), behind) {
+                log::debug!("node {}: {} is behind, re-sending our decision {:?}", me, from, value);
+                // In the sender's term, so the Commit isn't dismissed as stale
+                let certificate = state.certificate.clone();
+                o.send(src, ConsensusMsg::Commit { value, term, slot: state.slot, certificate });
+                return;
+            }
+        }
+
+        if let Some(term) = msg.term() {
+            if term < state.term {
+                // Left over from a round we've already moved past
+                log::trace!("node {}: ignoring {:?} from {}: we're in term {}", me, msg, from, state.term);
+                return;
+            }
+            if term > state.term && state.decided_value.is_none() {
+                // A newer round supersedes whatever we were doing in this one
+                let state = state.to_mut();
+                state.term = term;
+                state.role = NodeRole::Follower;
+                state.proposed_value = None;
+                state.votes_received.clear();
+                state.vote_log.clear();
+                state.promises.clear();
+                state.rivals.clear();
+                state.stalls = 0;
+                state.has_proposed_in_term = false;
+            }
+        }
+
+        match msg {
+            ConsensusMsg::Propose { value, term, slot } => {
+                if self.byzantine {
+                    // Equivocate: back every proposal, even ones competing with our own
+                    o.send(src, ConsensusMsg::Vote { value, term, slot });
+                } else if state.role == NodeRole::Follower
+                    && state.proposed_value.is_none()
+                    && state.locked.is_none_or(|locked| locked == value)
+                    && self.is_valid(&value)
+                {
+                    // Follower receives a proposal and joins the proposer's view
+                    log::debug!("node {}: accepted proposal {:?} from {} in term {}", me, value, from, term);
+                    let state = state.to_mut();
+                    state.proposed_value = Some(value);
+                    state.term = term;
+                    // Vote for the proposal
+                    o.send(src, ConsensusMsg::Vote { value, term, slot });
+                } else if src == id && state.role == NodeRole::Candidate {
+                    // Our own proposal looped back: vote for it the same way
+                    o.send(src, ConsensusMsg::Vote { value, term, slot });
+                } else if self.tie_break
+                    && state.role == NodeRole::Candidate
+                    && state.proposed_value.is_some_and(|mine| value > mine)
+                    && state.locked.is_none_or(|locked| locked == value)
+                    && self.is_valid(&value)
+                {
+                    // Back the larger bid instead. The votes we collected die
+                    // with our bid, including our own, so this is still the
+                    // only vote we count towards anything in this term.
+                    log::debug!("node {}: dropping our bid for the larger {:?} from {}", me, value, from);
+                    let state = state.to_mut();
+                    state.role = NodeRole::Follower;
+                    state.proposed_value = Some(value);
+                    state.votes_received.clear();
+                    state.vote_log.clear();
+                    o.send(src, ConsensusMsg::Vote { value, term, slot });
+                } else if self.abort_after.is_some()
+                    && state.role == NodeRole::Candidate
+                    && state.proposed_value.is_some_and(|mine| value != mine && (!self.tie_break || value > mine))
+                {
+                    // A rival that won't back us: with tie-breaking it would
+                    // still drop a smaller bid for ours
+                    log::debug!("node {}: {} is campaigning against us for {:?}", me, from, value);
+                    state.to_mut().rivals.insert(src);
+                } else {
+                    log::trace!(
+                        "node {}: ignoring proposal {:?} from {}: {:?}, already backing {:?}, locked on {:?}",
+                        me, value, from, state.role, state.proposed_value, state.locked
+                    );
+                }
+            }
+
+            ConsensusMsg::Vote { value, term, .. } => {
+                // Candidate collects votes, but only ones cast in its current view -
+                // a stale vote from an earlier view can't back a decision in this one
+                if state.role == NodeRole::Candidate && term == state.term {
+                    let state = state.to_mut();
+                    // Votes for other values are tallied too, so a split is visible
+                    state.votes_received.entry(value).or_default().insert(src);
+                    state.vote_log.push((src, value));
+                    state.vote_terms.insert((src, value, term));
+                    log::debug!("node {}: recorded vote for {:?} from {} in term {}", me, value, from, term);
+
+                    // Only a quorum for our own bid makes us Leader; one for a
+                    // value we never proposed is some other Candidate's win
+                    if state.proposed_value == Some(value) && self.has_quorum_for(&state.votes_received, value) {
+                        log::debug!("node {}: quorum reached for {:?} in term {}, leading", me, value, term);
+                        state.role = NodeRole::Leader;
+                        self.lead(id, state, value, o);
+                    }
+                } else {
+                    log::trace!("node {}: ignoring vote from {}: {:?} in term {}", me, from, state.role, state.term);
+                }
+            }
+
+            ConsensusMsg::Prepare { value, term, slot, .. } => {
+                // Lock in the leader's value, unless we already promised another
+                if state.decided_value.is_none() && state.locked.is_none_or(|locked| locked == value) {
+                    state.to_mut().locked = Some(value);
+                    o.send(src, ConsensusMsg::Promise { value, term, slot });
+                }
+            }
+
+            ConsensusMsg::Promise { value, term, .. } => {
+                if state.role == NodeRole::Leader && term == state.term && state.locked == Some(value) {
+                    let state = state.to_mut();
+                    let had_quorum = self.has_quorum(&state.promises);
+                    state.promises.insert(src);
+                    // Commit once, on the promise that completes the quorum
+                    if !had_quorum && self.has_quorum(&state.promises) {
+                        log::debug!("node {}: promise quorum reached for {:?}, committing", me, value);
+                        self.commit(id, state, value, o);
+                    }
+                }
+            }
+
+            ConsensusMsg::Commit { certificate, .. } if !self.certified(&certificate) => {
+                log::trace!("node {}: ignoring commit from {}: {:?} is no quorum", me, from, certificate);
+            }
+
+            ConsensusMsg::Commit { value, term, slot, certificate } => {
+                // Any node can receive commit and decide, with `commit_quorum`
+                // once enough others say the same
+                let undecided = state.decided_value.is_none();
+                let commit = ConsensusMsg::Commit { value, term, slot, certificate: certificate.clone() };
+                if undecided && self.commit_quorum && !self.tally_commit(id, state, src, value, commit, o) {
+                    log::trace!("node {}: commit for {:?} from {}, no quorum of commits yet", me, value, from);
+                } else if undecided {
+                    log::debug!("node {}: commit applied, decided {:?} in term {}", me, value, term);
+                    if self.gossip && src != id {
+                        // The sender has it already
+                        let mut gossip = Effects::default();
+                        let msg = ConsensusMsg::Commit { value, term, slot, certificate: certificate.clone() };
+                        self.broadcast(id, msg, &mut gossip);
+                        for (peer, msg) in gossip.sends.into_iter().filter(|&(peer, _)| peer != src) {
+                            o.send(peer, msg);
+                        }
+                    }
+                    self.decide(id, state.to_mut(), value, certificate, o);
+                } else if state.decided_value != Some(value) {
+                    Self::trap_conflicting_commit(id, state, src, value);
+                } else {
+                    log::trace!("node {}: ignoring commit from {}: already decided {:?}", me, from, state.decided_value);
+                }
+                // Acknowledge even a repeat: the first ack may have been lost
+                if self.acks_commits() && src != id {
+                    o.send(src, ConsensusMsg::CommitAck { term, slot });
+                }
+            }
+
+            ConsensusMsg::CommitAck { slot, .. } => {
+                // Retries go on until everyone we can reach has it, since a
+                // quorum alone can leave the rest undecided for good; plain
+                // acks are counted to the last so the Leader sees everyone who decided
+                let counting = self.ack_commits && slot == state.slot && !state.commit_acks.is_empty();
+                if state.pending_commit.is_some() || (counting && !state.commit_acks.contains(&src)) {
+                    let state = state.to_mut();
+                    state.commit_acks.insert(src);
+                    if self.peer_ids.iter().all(|peer| state.commit_acks.contains(peer) || !self.reaches(peer)) {
+                        state.pending_commit = None;
+                    }
+                }
+            }
+
+            ConsensusMsg::StateRequest => {
+                o.send(
+                    src,
+                    ConsensusMsg::StateResponse {
+                        decided_value: state.decided_value,
+                        view: state.term,
+                        certificate: state.certificate.clone(),
+                    },
+                );
+            }
+
+            ConsensusMsg::StateResponse { decided_value: Some(value), view, certificate } => {
+                // Only adopt a decision a quorum of real peers stands behind;
+                // a bare claim from one peer proves nothing
+                if state.decided_value.is_none() && self.certified(&certificate) {
+                    log::debug!("node {}: adopted {:?} from {}'s certified decision", me, value, from);
+                    let state = state.to_mut();
+                    state.term = view;
+                    self.decide(id, state, value, certificate, o);
+                }
+            }
+            ConsensusMsg::StateResponse { decided_value: None, .. } => {}
+
+            ConsensusMsg::ClientRequest { value } => {
+                // Only a node with nothing on the go takes a request on; the
+                // campaign then plays by the current term's rules like any other
+                if state.role == NodeRole::Follower
+                    && state.proposed_value.is_none()
+                    && !state.has_proposed_in_term
+                    && state.locked.is_none_or(|locked| locked == value)
+                    && self.is_valid(&value)
+                {
+                    log::debug!("node {}: client asked for {:?}, campaigning in term {}", me, value, state.term);
+                    self.campaign(id, state.to_mut(), value, o);
+                } else {
+                    log::trace!("node {}: ignoring client request for {:?}: {:?}", me, value, state.role);
+                }
+            }
+        }
+    }
+
+    /// Node `id`, configured as this actor, handles `msg` from `src` in
+    /// `state`: [`step`] for any of the optional behaviours. Returns the next
+    /// state and what to send, counting the message in `rejected` if it
+    /// changed nothing and sent nothing.
+    pub fn step(&self, id: Id, state: &ConsensusState<V>, src: Id, msg: &ConsensusMsg<V>) -> (ConsensusState<V>, Effects<V>) {
+        let mut next = Cow::Borrowed(state);
+        let mut effects = Effects::default();
+        self.transition(id, &mut next, src, msg.clone(), &mut effects);
+        let rejected = !self.crashed && effects.is_empty() && *next == *state;
+        let mut next = next.into_owned();
+        // Counted here rather than in transition, or the checker would see
+        // every ignored delivery as a state of its own
+        if rejected {
+            next.rejected += 1;
+        }
+        (next, effects)
+    }
+
+    /// Everything a delivery does to a node, as [`step`] and `on_msg` both
+    /// run it. Leaves `state` borrowed when nothing changed, which lets the
+    /// checker skip the delivery.
+    fn transition(&self, id: Id, state: &mut Cow<ConsensusState<V>>, src: Id, msg: ConsensusMsg<V>, effects: &mut impl Outbox<V>) {
+        if self.crashed {
+            return;
+        }
+
+        self.handle_msg(id, state, src, msg, effects);
+        // Moving on to a new slot releases whatever arrived early for it
+        while let Some((src, msg)) = state.deferred.iter().find(|(_, msg)| msg.slot() == Some(state.slot)).cloned() {
+            state.to_mut().deferred.remove(&(src, msg.clone()));
+            self.handle_msg(id, state, src, msg, effects);
+        }
+    }
+}
+
+/// How many times each envelope has been delivered. Kept as model history so
+/// a duplicating network can be bounded to at most `max_dupes` deliveries per
+/// message instead of exploding the state space. With `max_withheld` it also
+/// counts sends, which is enough to tell how many messages were dropped.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DeliveryCounts {
+    pub max_dupes: Option<usize>,
+    pub counts: BTreeMap<(Id, Id, ConsensusMsg), usize>,
+    /// Most messages the network may withhold for good, see [`SimConfig::adversary`]
+    pub max_withheld: Option<usize>,
+    pub sent: usize,
+}
+
+impl DeliveryCounts {
+    pub fn new(max_dupes: Option<usize>) -> Self {
+        DeliveryCounts {
+            max_dupes,
+            counts: BTreeMap::new(),
+            max_withheld: None,
+            sent: 0,
+        }
+    }
+
+    /// `record_msg_in` hook. Unbounded runs skip tracking so they don't pay for it.
+    pub fn record_in<C>(_cfg: &C, history: &Self, env: Envelope<&ConsensusMsg>) -> Option<Self> {
+        history.max_dupes.or(history.max_withheld)?;
+        let mut next = history.clone();
+        *next.counts.entry((env.src, env.dst, env.msg.clone())).or_insert(0) += 1;
+        Some(next)
+    }
+
+    /// `record_msg_out` hook, only tracking when messages may be withheld
+    pub fn record_out<C>(_cfg: &C, history: &Self, _env: Envelope<&ConsensusMsg>) -> Option<Self> {
+        history.max_withheld?;
+        Some(DeliveryCounts {
+            sent: history.sent + 1,
+            ..history.clone()
+        })
+    }
+
+    /// Messages sent but neither delivered nor still in `network`, i.e.
+    /// dropped. Only meaningful on a non-duplicating network with
+    /// `max_withheld` set. Deliveries that change nothing never happen on an
+    /// unordered network, so those messages just stay in flight.
+    pub fn withheld(&self, network: &Network<ConsensusMsg>) -> usize {
+        let delivered: usize = self.counts.values().sum();
+        self.sent.saturating_sub(delivered + network.len())
+    }
+
+    pub fn within_limit(&self) -> bool {
+        match self.max_dupes {
+            Some(max) => self.counts.values().all(|&count| count <= max),
+            None => true,
+        }
+    }
+
+    /// Duplication only makes sense with a network that keeps delivered messages around
+    pub fn network(&self) -> Network<ConsensusMsg> {
+        match self.max_dupes {
+            Some(_) => Network::new_unordered_duplicating([]),
+            None => Network::new_unordered_nonduplicating([]),
+        }
+    }
+}
+
+impl Rewrite<Id> for DeliveryCounts {
+    fn rewrite<S>(&self, plan: &RewritePlan<Id, S>) -> Self {
+        DeliveryCounts {
+            max_dupes: self.max_dupes,
+            max_withheld: self.max_withheld,
+            sent: self.sent,
+            counts: self
+                .counts
+                .iter()
+                .map(|((src, dst, msg), &count)| ((src.rewrite(plan), dst.rewrite(plan), msg.rewrite(plan)), count))
+                .collect(),
+        }
+    }
+}
+
+/// The cluster as configured, kept as the model's `cfg` so property closures
+/// can read it from their first argument instead of guessing from the states
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ModelConfig {
+    pub nodes: usize,
+    /// Node indices that are crashed or Byzantine, whose states say nothing
+    /// about what the honest ones agreed on
+    pub faulty: BTreeSet<usize>,
+    /// The faulty nodes that are down from the start
+    pub crashed: BTreeSet<usize>,
+    /// Stake per node, indexed like the actors
+    pub weights: Vec<u64>,
+    /// The value domain every decision must come from
+    pub values: Vec<Value>,
+    /// Stake it takes to decide, one figure for the whole cluster. Nodes
+    /// given their own membership in [`SimConfig::views`] count a majority
+    /// of that view instead, which this doesn't capture.
+    pub quorum: usize,
+}
+
+impl ModelConfig {
+    /// Node indices that aren't faulty
+    pub fn honest(&self) -> HashSet<usize> {
+        (0..self.nodes).filter(|i| !self.faulty.contains(i)).collect()
+    }
+
+    /// Whether `voters` hold `quorum` stake between them; a node without a
+    /// listed weight counts once
+    pub fn is_quorum(&self, voters: &HashSet<Id>) -> bool {
+        let stake: u64 = voters.iter().map(|&voter| self.weights.get(usize::from(voter)).copied().unwrap_or(1)).sum();
+        stake >= self.quorum as u64
+    }
+}
+
+pub type ConsensusModel = ActorModel<ConsensusActor, ModelConfig, DeliveryCounts>;
+pub type ConsensusModelState = ActorModelState<ConsensusActor, DeliveryCounts>;
+pub type ConsensusPath = Path<ConsensusModelState, ActorModelAction<ConsensusMsg, Timer>>;
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+/// Experiment parameters, loadable from a JSON file so batch runs don't need recompiling
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimConfig {
+    pub nodes: usize,
+    /// Explicit quorum size; `None` means simple majority
+    pub quorum: Option<usize>,
+    pub proposer: usize,
+    pub proposal: Value,
+    /// A second node campaigning for its own value at the same time
+    pub rival: Option<(usize, Value)>,
+    /// Check [`SimConfig::contention_scenarios`], a rival against every
+    /// proposal, instead of one uncontested proposal per value
+    pub contention: bool,
+    /// Value domain: every proposal must come from here
+    pub values: Vec<Value>,
+    /// Indices of nodes that are down from the start
+    pub crashed: Vec<usize>,
+    /// How many nodes are down from the start, as configs before `crashed`
+    /// put it: the highest-numbered ones not already in `crashed`
+    #[deprecated(note = "name the nodes in `crashed` instead")]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub crash_faults: usize,
+    /// Indices of nodes that ask their peers for the decision on startup
+    pub recovering: Vec<usize>,
+    pub byzantine_faults: usize,
+    /// Groups of node indices that can only talk among themselves; empty means no partition
+    pub partitions: Vec<Vec<usize>>,
+    /// Any rate above zero makes the network lossy. The checker explores every
+    /// drop anyway, so the exact rate doesn't change what's reachable.
+    pub loss_rate: f64,
+    /// Deliver each message at most this many times; `None` means no duplication
+    pub max_dupes: Option<usize>,
+    /// Deliver messages between any two nodes in the order they were sent.
+    /// Can't be combined with `max_dupes`.
+    pub ordered: bool,
+    /// Every node campaigns at start, node i for the i-th value, which
+    /// overrides `proposer` and `rival`. Needs a value per node.
+    pub all_propose: bool,
+    /// The network may withhold any one message forever: the adversary from
+    /// the FLP proof, which needs only a single delayed message to keep a
+    /// run from deciding. Nothing is retransmitted, so delaying a message
+    /// for ever is the same as dropping it, and the checker tries every
+    /// choice of message. Can't be combined with loss or duplication.
+    pub adversary: bool,
+    /// How [`SimConfig::check_report`] explores the model
+    pub search: Search,
+    /// Stop exploring this many steps from the initial state; `None` means unbounded
+    pub max_depth: Option<usize>,
+    /// Stop exploring each scenario after this many unique states, leaving
+    /// the check incomplete; `None` means no cap
+    pub max_states: Option<usize>,
+    /// Misconfigured membership: node -> the nodes it believes make up the
+    /// cluster. It only broadcasts to those, only counts their votes, and
+    /// without an explicit `quorum` takes a majority of them. Nodes not
+    /// listed see everyone.
+    pub views: BTreeMap<usize, Vec<usize>>,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        SimConfig {
+            nodes: 3,
+            quorum: None,
+            proposer: 0,
+            proposal: Value::V0,
+            rival: None,
+            contention: false,
+            values: Value::ALL.to_vec(),
+            crashed: vec![],
+            #[allow(deprecated)]
+            crash_faults: 0,
+            recovering: vec![],
+            byzantine_faults: 0,
+            partitions: vec![],
+            loss_rate: 0.0,
+            max_dupes: None,
+            ordered: false,
+            all_propose: false,
+            adversary: false,
+            search: Search::Bfs,
+            max_depth: None,
+            max_states: None,
+            views: BTreeMap::new(),
+        }
+    }
+}
+
+impl SimConfig {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn quorum_size(&self) -> usize {
+        self.quorum.unwrap_or(self.nodes / 2 + 1)
+    }
+
+    /// The default cluster, grown if need be to cover every node `trace`
+    /// mentions. A best guess for replaying a trace that came without its
+    /// config: right for runs of `check` without a rival.
+    pub fn for_trace(trace: &[DeliveryRecord]) -> SimConfig {
+        let default = SimConfig::default();
+        let mentioned = trace.iter().flat_map(|d| [d.src, d.dst]).map(|id| usize::from(id) + 1).max();
+        SimConfig {
+            nodes: mentioned.unwrap_or(0).max(default.nodes),
+            ..default
+        }
+    }
+
+    /// One actor per node, each over its own view of the membership, with
+    /// the proposer, crashes and partitions applied. All share the configured
+    /// quorum, or take a majority of their view without one. The first
+    /// `byzantine_faults` nodes are Byzantine.
+    pub fn actors(&self) -> Vec<ConsensusActor> {
+        let everyone: Vec<usize> = (0..self.nodes).collect();
+        let crashed = self.crashed_nodes();
+        (0..self.nodes)
+            .map(|i| {
+                let view = self.views.get(&i).unwrap_or(&everyone);
+                let mut actor = ConsensusActor::new(view.iter().copied().map(Id::from).collect()).with_leader(None);
+                if let Some(quorum) = self.quorum {
+                    actor = actor.with_quorum_policy(Threshold(quorum));
+                }
+                actor = actor
+                    .with_crashed(crashed.contains(&i))
+                    .with_recovering(self.recovering.contains(&i))
+                    .with_byzantine(i < self.byzantine_faults);
+                if self.all_propose {
+                    actor = actor.with_proposal(self.values[i]);
+                } else if i == self.proposer {
+                    actor = actor.with_proposal(self.proposal);
+                }
+                if let Some((_, value)) = self.rival.filter(|&(node, _)| node == i && !self.all_propose) {
+                    actor = actor.with_proposal(value);
+                }
+                if let Some(group) = self.partitions.iter().find(|g| g.contains(&i)) {
+                    actor = actor.with_reachable(group.iter().copied().map(Id::from).collect());
+                }
+                actor
+            })
+            .collect()
+    }
+
+    /// Every node that's down from the start: `crashed` plus whatever the
+    /// deprecated `crash_faults` adds
+    pub fn crashed_nodes(&self) -> Vec<usize> {
+        #[allow(deprecated)]
+        let extra = self.crash_faults;
+        let mut nodes = self.crashed.clone();
+        nodes.extend((0..self.nodes).rev().filter(|i| !self.crashed.contains(i)).take(extra));
+        nodes
+    }
+
+    /// [`min_faulty_to_break_agreement`] for this cluster: the least of
+    /// what each node's own membership view and quorum allow
+    pub fn safety_margin(&self) -> usize {
+        self.actors().iter().map(ConsensusActor::min_faulty_to_break_agreement).min().unwrap_or(0)
+    }
+
+    /// Whether the nodes are interchangeable once started, so renaming node
+    /// ids can't change what happens next and symmetry reduction is sound.
+    /// Proposals only matter at startup; crashes, recovery, Byzantine nodes,
+    /// partitions and membership views single out particular ids for the
+    /// whole run.
+    pub fn is_symmetric(&self) -> bool {
+        self.crashed_nodes().is_empty()
+            && self.recovering.is_empty()
+            && self.byzantine_faults == 0
+            && self.partitions.is_empty()
+            && self.views.is_empty()
+    }
+
+    /// The network the model starts with: FIFO channels when `ordered`,
+    /// otherwise unordered, redelivering each message up to `max_dupes`
+    /// times if set. [`NetworkKind`] has what each one changes.
+    pub fn network(&self) -> Network<ConsensusMsg> {
+        if self.ordered {
+            Network::new_ordered([])
+        } else {
+            DeliveryCounts::new(self.max_dupes).network()
+        }
+    }
+
+    /// What the model's properties get to know about this cluster
+    pub fn model_config(&self) -> ModelConfig {
+        ModelConfig {
+            nodes: self.nodes,
+            faulty: self.crashed_nodes().into_iter().chain(0..self.byzantine_faults).collect(),
+            crashed: self.crashed_nodes().into_iter().collect(),
+            weights: vec![1; self.nodes],
+            values: self.values.clone(),
+            quorum: self.quorum_size(),
+        }
+    }
+
+    pub fn build_model(&self) -> ConsensusModel {
+        let lossy = if self.loss_rate > 0.0 || self.adversary { LossyNetwork::Yes } else { LossyNetwork::No };
+        let history = DeliveryCounts {
+            max_withheld: self.adversary.then_some(1),
+            ..DeliveryCounts::new(self.max_dupes)
+        };
+        let model = ActorModel::new(self.model_config(), history.clone())
+            .actors(self.actors())
+            .init_network(self.network())
+            .lossy_network(lossy)
+            .record_msg_in(DeliveryCounts::record_in)
+            .record_msg_out(DeliveryCounts::record_out)
+            .within_boundary(|_, state| {
+                state.history.within_limit()
+                    && state.history.max_withheld.is_none_or(|max| state.history.withheld(&state.network) <= max)
+            });
+        with_properties(model, standard_properties())
+    }
+
+    /// [`SimConfig::build_model`], but refusing a config that fails
+    /// [`SimConfig::validate`]. A quorum larger than the cluster, say, would
+    /// otherwise give a model that explores every interleaving without ever
+    /// being able to decide.
+    pub fn try_build_model(&self) -> Result<ConsensusModel, Vec<String>> {
+        self.validate()?;
+        Ok(self.build_model())
+    }
+
+    /// Checks just this config, without the contention scenarios, and sums
+    /// it up as [`SimConfig::check_report`] would
+    pub fn run_check(&self) -> CheckSummary {
+        let result = run_search(self.build_model(), self.search, self.max_depth, self.max_states, DEFAULT_THREADS);
+        let mut tally = CheckTally::new(self);
+        tally.add(self, &*result);
+        let report = tally.finish();
+        CheckSummary {
+            unique_states: report.states_explored,
+            violations: SafetyProperty::ALL.iter().map(|p| p.name()).filter(|&name| report.failed(name)).collect(),
+            progress: report.properties.get("Progress") == Some(&Verdict::Pass),
+            wasted_messages: result.discovery("Progress").map(wasted_deliveries),
+        }
+    }
+
+    /// The config whose runs are exactly this one's weakly fair schedules,
+    /// for checking liveness. Fairness here means every message in flight is
+    /// eventually delivered: the network may still delay and reorder, but
+    /// can't keep a message from its destination forever. A drop removes the
+    /// only copy of a message for good, since nothing is retransmitted, so a
+    /// fair run never drops anything, and what's left is this config with
+    /// loss and the adversary turned off. Safety should still be checked against every drop.
+    pub fn fair_schedules(&self) -> SimConfig {
+        SimConfig {
+            loss_rate: 0.0,
+            adversary: false,
+            ..self.clone()
+        }
+    }
+
+    /// What [`SimConfig::check_report`] checks: the contention scenarios if
+    /// asked for, otherwise one config per value in the domain with the
+    /// proposer campaigning for it unopposed. Just this config when every
+    /// node already campaigns for its own value.
+    pub fn scenarios(&self) -> Vec<SimConfig> {
+        if self.contention || self.all_propose || self.values.is_empty() {
+            return self.contention_scenarios();
+        }
+        self.values.iter().map(|&proposal| SimConfig { proposal, ..self.clone() }).collect()
+    }
+
+    /// One config per ordered pair of distinct values in the domain: the
+    /// proposer campaigns for the first and the next node for the second.
+    /// Falls back to just this config when there's nothing to contend over,
+    /// or when every node already campaigns for its own value.
+    pub fn contention_scenarios(&self) -> Vec<SimConfig> {
+        if self.nodes < 2 || self.values.len() < 2 || self.all_propose {
+            return vec![self.clone()];
+        }
+        let rival = (self.proposer + 1) % self.nodes;
+        let mut scenarios = Vec::new();
+        for &proposal in &self.values {
+            for &other in self.values.iter().filter(|&&v| v != proposal) {
+                scenarios.push(SimConfig {
+                    proposal,
+                    rival: Some((rival, other)),
+                    ..self.clone()
+                });
+            }
+        }
+        scenarios
+    }
+
+    /// Checks each of [`SimConfig::scenarios`] against the standard
+    /// properties with this config's search, and gathers the results the way `check` reports
+    /// them. `check` layers its liveness, fairness and symmetry options on
+    /// top; this is the part a library user needs. Searches with
+    /// [`DEFAULT_THREADS`].
+    pub fn check_report(&self) -> CheckReport {
+        self.check_report_with(DEFAULT_THREADS)
+    }
+
+    /// [`SimConfig::check_report`] on `threads` checker threads, which only
+    /// changes how fast the report comes, not what it says
+    pub fn check_report_with(&self, threads: usize) -> CheckReport {
+        let mut tally = CheckTally::new(self);
+        for scenario in self.scenarios() {
+            let result = run_search(scenario.build_model(), self.search, self.max_depth, self.max_states, threads);
+            tally.add(&scenario, &*result);
+        }
+        tally.finish()
+    }
+
+    /// [`SimConfig::check_report`], reusing the report stored in `dir` for
+    /// this exact config if there is one and storing it there otherwise
+    pub fn cached_check_report(&self, dir: &std::path::Path) -> std::io::Result<CheckReport> {
+        if let Some(report) = load_cached_report(dir, self) {
+            return Ok(report);
+        }
+        let report = self.check_report();
+        store_cached_report(dir, self, &report)?;
+        Ok(report)
+    }
+
+    /// Checks every contention scenario and folds the results together:
+    /// states are summed, and a property fails if it fails in any scenario.
+    pub fn run_contention_check(&self) -> CheckSummary {
+        let mut total = CheckSummary {
+            unique_states: 0,
+            violations: Vec::new(),
+            progress: false,
+            wasted_messages: None,
+        };
+        for scenario in self.contention_scenarios() {
+            let summary = scenario.run_check();
+            total.unique_states += summary.unique_states;
+            for name in summary.violations {
+                if !total.violations.contains(&name) {
+                    total.violations.push(name);
+                }
+            }
+            total.progress |= summary.progress;
+            total.wasted_messages = total.wasted_messages.or(summary.wasted_messages);
+        }
+        total
+    }
+
+    /// Runs every static check and reports *all* problems, not just the first,
+    /// so a broken experiment file can be fixed in one pass.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        let quorum = self.quorum_size();
+
+        if self.nodes == 0 {
+            problems.push("nodes must be at least 1".to_string());
+        }
+        if quorum == 0 {
+            problems.push("quorum must be at least 1".to_string());
+        }
+        if quorum > self.nodes {
+            problems.push(format!(
+                "quorum {} exceeds node count {}",
+                quorum, self.nodes
+            ));
+        }
+        // Two quorums must share at least one node, otherwise two disjoint
+        // groups can decide different values
+        if let Err(problem) = Threshold(quorum).validate(self.nodes) {
+            problems.push(problem);
+        }
+        if self.proposer >= self.nodes {
+            problems.push(format!(
+                "proposer {} out of range for {} nodes",
+                self.proposer, self.nodes
+            ));
+        }
+        if self.values.is_empty() {
+            problems.push("values must not be empty".to_string());
+        } else if !self.values.contains(&self.proposal) {
+            problems.push(format!("proposal {:?} is not one of the values", self.proposal));
+        }
+        if let Some((node, value)) = self.rival {
+            if node >= self.nodes {
+                problems.push(format!("rival {} out of range for {} nodes", node, self.nodes));
+            } else if node == self.proposer {
+                problems.push(format!("rival {} is also the proposer", node));
+            }
+            if !self.values.contains(&value) {
+                problems.push(format!("rival proposal {:?} is not one of the values", value));
+            }
+        }
+        let listed = self
+            .crashed
+            .iter()
+            .chain(&self.recovering)
+            .chain(self.partitions.iter().flatten())
+            .chain(self.views.keys())
+            .chain(self.views.values().flatten());
+        for &node in listed {
+            if node >= self.nodes {
+                problems.push(format!("node {} out of range for {} nodes", node, self.nodes));
+            }
+        }
+        // Each node decides on its own view's quorum, so each view needs one
+        // it can reach, and the node itself among the members
+        for (&node, view) in &self.views {
+            let view_quorum = self.quorum.unwrap_or(view.len() / 2 + 1);
+            if view_quorum > view.len() {
+                problems.push(format!("quorum {} exceeds the {} nodes in node {}'s view", view_quorum, view.len(), node));
+            }
+            if !view.contains(&node) {
+                problems.push(format!("node {}'s view leaves out node {} itself", node, node));
+            }
+        }
+        if self.all_propose && self.values.len() < self.nodes {
+            problems.push(format!(
+                "all nodes proposing needs a distinct value for each of {} nodes, got {}",
+                self.nodes,
+                self.values.len()
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.loss_rate) {
+            problems.push(format!("loss rate {} must be between 0 and 1", self.loss_rate));
+        }
+        if self.max_dupes == Some(0) {
+            problems.push("max dupes must be at least 1".to_string());
+        }
+        if self.ordered && self.max_dupes.is_some() {
+            problems.push("an ordered network can't also duplicate messages".to_string());
+        }
+        if self.adversary && self.max_dupes.is_some() {
+            problems.push("the adversary can't withhold messages on a duplicating network".to_string());
+        }
+        if self.adversary && self.loss_rate > 0.0 {
+            problems.push("the adversary withholds one message, loss already drops any number".to_string());
+        }
+        let faults = self.crashed_nodes().len() + self.byzantine_faults;
+        if self.nodes > 0 && faults >= self.nodes {
+            problems.push(format!(
+                "fault count {} must be less than node count {}",
+                faults, self.nodes
+            ));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+/// Textbook minimum quorum for `byzantine` faults among `nodes`: any two
+/// quorums must overlap in more than `byzantine` nodes, i.e. q > (n + f) / 2.
+/// For n = 3f + 1 this is the familiar 2f + 1.
+pub fn byzantine_quorum_bound(nodes: usize, byzantine: usize) -> usize {
+    (nodes + byzantine) / 2 + 1
+}
+
+/// Fewest faulty nodes out of `node_count` before Agreement can break under
+/// `quorum_policy`: n - q + 1, the count that leaves the honest nodes short
+/// of a quorum of their own. From there every quorum needs a faulty member,
+/// and one voting both ways can help two quorums decide different values.
+/// For a simple majority that's ⌈n/2⌉. A policy that accepts no quorum at
+/// all gives 0, as no quorum is ever all honest.
+pub fn min_faulty_to_break_agreement(node_count: usize, quorum_policy: &dyn QuorumPolicy) -> usize {
+    let quorum = (0..=node_count)
+        .find(|&votes| quorum_policy.is_quorum(votes, node_count))
+        .unwrap_or(node_count + 1);
+    (node_count + 1).saturating_sub(quorum)
+}
+
+/// The worst split we can stage: nodes 0 and 1 campaign for V0 and V1 at the
+/// same time while the first `byzantine` nodes (including both candidates
+/// once `byzantine >= 2`) vote for everything.
+pub fn byzantine_split_model(nodes: usize, byzantine: usize, quorum: usize) -> ConsensusModel {
+    SimConfig {
+        nodes,
+        quorum: Some(quorum),
+        proposer: 0,
+        proposal: Value::V0,
+        rival: Some((1, Value::V1)),
+        byzantine_faults: byzantine,
+        ..SimConfig::default()
+    }
+    .build_model()
+}
+
+/// Smallest quorum for which the checker can't break Agreement in
+/// [`byzantine_split_model`], or `None` if even `quorum == nodes` is unsafe.
+pub fn min_safe_quorum(nodes: usize, byzantine: usize) -> Option<usize> {
+    (1..=nodes).find(|&quorum| {
+        let result = byzantine_split_model(nodes, byzantine, quorum)
+            .checker()
+            .finish_when(HasDiscoveries::AnyOf(["Agreement"].into_iter().collect()))
+            .spawn_bfs()
+            .join();
+        result.discovery("Agreement").is_none()
+    })
+}
+
+/// Sanity floor on exploration: a model that deadlocks right away (say, no
+/// node ever proposes) explores a handful of states and passes every
+/// safety check vacuously.
+pub fn check_min_states(explored: usize, min_states: usize) -> Result<(), String> {
+    if explored < min_states {
+        Err(format!(
+            "Suspiciously few states explored ({} < {}) — protocol may be stuck",
+            explored, min_states
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Outcome of a model-checking run
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckSummary {
+    pub unique_states: usize,
+    /// Names of the safety properties with a counterexample
+    pub violations: Vec<&'static str>,
+    /// Whether any reachable state has a decision
+    pub progress: bool,
+    /// Deliveries along the shortest decision path that left the recipient's
+    /// state untouched; `None` when nothing was decided
+    pub wasted_messages: Option<usize>,
+}
+
+/// Folds the searches of a config's scenarios into one [`CheckReport`]:
+/// states are summed, a property fails if it fails in any scenario, and the
+/// shortest counterexample found is kept. [`SimConfig::check_report`] and
+/// `check` both report through this, `check` adding its liveness verdicts
+/// to the finished report.
+pub struct CheckTally {
+    report: CheckReport,
+    /// Per failed property: (steps, summary)
+    shortest: BTreeMap<&'static str, (usize, String)>,
+    progress: bool,
+    violated: bool,
+    depth_bounded: bool,
+    /// The model's own boundary, duplication or withholding, leaves states out
+    model_bounded: bool,
+    max_depth: Option<usize>,
+    max_states: Option<usize>,
+    started: Instant,
+}
+
+impl CheckTally {
+    /// An empty tally for the scenarios of `config`, timed from now
+    pub fn new(config: &SimConfig) -> Self {
+        CheckTally {
+            report: CheckReport {
+                safety_margin: config.safety_margin(),
+                shortest: config.search == Search::Bfs,
+                ..CheckReport::default()
+            },
+            shortest: BTreeMap::new(),
+            progress: false,
+            violated: false,
+            depth_bounded: false,
+            model_bounded: config.max_dupes.is_some() || config.adversary,
+            max_depth: config.max_depth,
+            max_states: config.max_states,
+            started: Instant::now(),
+        }
+    }
+
+    /// Counts the states `result` explored, and whether the state cap or
+    /// depth bound cut it short, without looking at its discoveries
+    pub fn count(&mut self, result: &dyn Checker<ConsensusModel>) {
+        self.report.states_explored += result.unique_state_count();
+        self.report.truncated |= hit_state_cap(result.unique_state_count(), self.max_states);
+        self.depth_bounded |= hit_depth_bound(result.max_depth(), self.max_depth);
+    }
+
+    /// Adds the search of `scenario`: its states, plus what it found for
+    /// the standard properties
+    pub fn add(&mut self, scenario: &SimConfig, result: &dyn Checker<ConsensusModel>) {
+        self.count(result);
+        let report = &mut self.report;
+        if let Some(path) = result.discovery("Progress") {
+            self.progress = true;
+            if report.decisions.is_empty() {
+                report.decisions = decision_histogram(&path.last_state().actor_states);
+            }
+            if report.messages.is_empty() {
+                report.messages = message_counts(&scenario.build_model(), path.clone());
+            }
+            if let Some(d) = decision_depth(path) {
+                report.decision_depth = Some(report.decision_depth.map_or(d, |best| best.min(d)));
+            }
+        }
+        if let Some(path) = result.discovery("ConsensusReached").filter(|_| report.consensus.is_none()) {
+            report.consensus = consensus_reached(&path.last_state().actor_states, scenario.quorum_size());
+        }
+        for property in SafetyProperty::ALL {
+            if let Some(path) = result.discovery(property.name()) {
+                let steps = path.clone().into_actions().len();
+                if self.shortest.get(property.name()).is_none_or(|&(best, _)| steps < best) {
+                    self.shortest.insert(property.name(), (steps, summarize_discovery(&path)));
+                }
+                if !self.violated {
+                    self.violated = true;
+                    report.decisions = decision_histogram(&path.last_state().actor_states);
+                }
+            }
+        }
+    }
+
+    /// The report on everything added, timed up to now
+    pub fn finish(self) -> CheckReport {
+        let mut report = self.report;
+        for property in SafetyProperty::ALL {
+            let verdict = if self.shortest.contains_key(property.name()) { Verdict::Fail } else { Verdict::Pass };
+            report.properties.insert(property.name(), verdict);
+        }
+        report.properties.insert("Progress", if self.progress { Verdict::Pass } else { Verdict::Pending });
+        report.counterexamples = self.shortest.into_iter().map(|(name, (_, summary))| (name, summary)).collect();
+        report.exhaustive = !report.truncated && !self.depth_bounded && !self.model_bounded;
+        report.duration = self.started.elapsed();
+        report
+    }
+}
+
+/// How a single property fared in a `check` run
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Pass,
+    Fail,
+    /// A Sometimes property with no example found
+    Pending,
+}
+
+/// What `check` exits with when every safety property held
+pub const EXIT_OK: i32 = 0;
+/// ...when some safety property was violated
+pub const EXIT_VIOLATION: i32 = 1;
+/// ...when the run couldn't go as asked: bad flags, an invalid config, or an
+/// output file it can't write
+pub const EXIT_MISCONFIGURED: i32 = 2;
+
+/// Everything `check` reports, gathered once so the text and JSON printers
+/// agree
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CheckReport {
+    pub states_explored: usize,
+    /// Verdict per property name, liveness checks only when enabled
+    #[serde(deserialize_with = "known_names::deserialize")]
+    pub properties: BTreeMap<&'static str, Verdict>,
+    /// A counterexample per failed property, the shortest found across
+    /// scenarios, as from [`summarize_discovery`]
+    #[serde(deserialize_with = "known_names::deserialize")]
+    pub counterexamples: BTreeMap<&'static str, String>,
+    /// Messages sent per kind along the first decision found, as from
+    /// [`message_counts`]; empty if nothing was decided
+    #[serde(deserialize_with = "known_names::deserialize")]
+    pub messages: BTreeMap<&'static str, usize>,
+    /// Fewest deliveries before a decision across the decision paths found,
+    /// as from [`decision_depth`]
+    pub decision_depth: Option<usize>,
+    /// Nodes per decided value at the end of the first counterexample, or of
+    /// the first decision path if nothing failed, as from
+    /// [`decision_histogram`]; empty if neither was found
+    pub decisions: BTreeMap<Value, usize>,
+    /// What a quorum decided on the first run found where one did, as from
+    /// [`consensus_reached`]; `None` if no run got that far
+    pub consensus: Option<Value>,
+    /// Exploration stopped at the state cap, so passing properties were only
+    /// checked on the states seen
+    pub truncated: bool,
+    /// Every reachable state was checked: neither the state cap nor the
+    /// depth bound cut any search short, no duplication cap or withholding
+    /// limit fenced the model in, and no symmetry reduction stood in for
+    /// states. Only then does a pass mean the property was verified rather
+    /// than just not violated within bounds.
+    pub exhaustive: bool,
+    /// Wall-clock time spent searching, all scenarios together
+    pub duration: Duration,
+    /// Faulty nodes it takes to break Agreement with this quorum, as from
+    /// [`min_faulty_to_break_agreement`]
+    pub safety_margin: usize,
+    /// Read back from a `--cache` directory rather than checked just now
+    #[serde(default)]
+    pub from_cache: bool,
+    /// The search was breadth-first, so each counterexample is a shortest
+    /// one rather than just the first found
+    #[serde(default)]
+    pub shortest: bool,
+}
+
+/// Reads the name-keyed maps of a [`CheckReport`] back in. The names are
+/// `&'static str`s, so each has to be one the checker could have written:
+/// a property from [`standard_properties`] or [`liveness_properties`], or a
+/// message kind.
+mod known_names {
+    use serde::{Deserialize, Deserializer};
+    use std::collections::BTreeMap;
+
+    fn intern(name: &str) -> Option<&'static str> {
+        let properties = crate::standard_properties().into_iter().chain(crate::liveness_properties());
+        properties
+            .map(|(_, known, _)| known)
+            .chain(crate::MESSAGE_KINDS)
+            .find(|&known| known == name)
+    }
+
+    pub fn deserialize<'de, T, D>(d: D) -> Result<BTreeMap<&'static str, T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        BTreeMap::<String, T>::deserialize(d)?
+            .into_iter()
+            .map(|(name, value)| match intern(&name) {
+                Some(known) => Ok((known, value)),
+                None => Err(serde::de::Error::custom(format!("unknown property or message kind '{}'", name))),
+            })
+            .collect()
+    }
+}
+
+/// A report as `--cache` stores it, next to the key it was checked for
+#[derive(Serialize, Deserialize)]
+struct CachedReport {
+    key: serde_json::Value,
+    report: CheckReport,
+}
+
+/// Version of what a cached [`CheckReport`] means; bump it whenever the
+/// report's fields or the checks behind them change
+pub const REPORT_FORMAT: u32 = 1;
+
+/// `key` tagged with the crate version and [`REPORT_FORMAT`], so a report
+/// checked by another build is never taken for this one's
+fn versioned_key<K: Serialize>(key: &K) -> serde_json::Result<serde_json::Value> {
+    Ok(serde_json::json!({
+        "crate_version": env!("CARGO_PKG_VERSION"),
+        "report_format": REPORT_FORMAT,
+        "run": serde_json::to_value(key)?,
+    }))
+}
+
+/// Where `--cache` keeps the report for `key`, any serializable
+/// description of the run such as its [`SimConfig`]: `dir` holds one file per
+/// key and build, named after an FNV-1a hash of the versioned key's JSON.
+/// Stable across builds, unlike std's hasher.
+pub fn report_cache_path<K: Serialize>(dir: &std::path::Path, key: &K) -> std::path::PathBuf {
+    let json = versioned_key(key).expect("run descriptions serialize").to_string();
+    let hash = json.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    dir.join(format!("report-{:016x}.json", hash))
+}
+
+/// The report stored for `key`, marked `from_cache`. `None` if there isn't
+/// one, or if the file is unreadable or was written for a different key or
+/// by another version, in which case it's checked again and overwritten.
+pub fn load_cached_report<K: Serialize>(dir: &std::path::Path, key: &K) -> Option<CheckReport> {
+    let json = std::fs::read_to_string(report_cache_path(dir, key)).ok()?;
+    let cached: CachedReport = serde_json::from_str(&json).ok()?;
+    if cached.key != versioned_key(key).ok()? {
+        return None;
+    }
+    Some(CheckReport { from_cache: true, ..cached.report })
+}
+
+/// Stores `report` as the result for `key`, creating `dir` if need be
+pub fn store_cached_report<K: Serialize>(dir: &std::path::Path, key: &K, report: &CheckReport) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let cached = CachedReport { key: versioned_key(key)?, report: report.clone() };
+    std::fs::write(report_cache_path(dir, key), serde_json::to_string_pretty(&cached)?)
+}
+
+impl CheckReport {
+    /// How fast the search went; 0 if it took no measurable time
+    pub fn states_per_sec(&self) -> f64 {
+        match self.duration.as_secs_f64() {
+            secs if secs > 0.0 => self.states_explored as f64 / secs,
+            _ => 0.0,
+        }
+    }
+
+    pub fn failed(&self, name: &str) -> bool {
+        self.properties.get(name) == Some(&Verdict::Fail)
+    }
+
+    /// [`EXIT_VIOLATION`] if any safety (`Always`) property failed, else
+    /// [`EXIT_OK`]. Liveness failures and a pending Progress don't count:
+    /// on a lossy network they're expected.
+    pub fn exit_code(&self) -> i32 {
+        if SafetyProperty::ALL.iter().any(|property| self.failed(property.name())) {
+            EXIT_VIOLATION
+        } else {
+            EXIT_OK
+        }
+    }
+
+    /// What a pass, or an example not found, is worth: `(verified)` after
+    /// an exhaustive search, `(within bounds)` otherwise. A counterexample
+    /// or an example found stands either way.
+    pub fn qualifier(&self) -> &'static str {
+        if self.exhaustive {
+            "(verified)"
+        } else {
+            "(within bounds)"
+        }
+    }
+
+    /// How to introduce a counterexample: only breadth-first search
+    /// guarantees it's a shortest one
+    pub fn counterexample_label(&self) -> &'static str {
+        if self.shortest {
+            "Shortest counterexample"
+        } else {
+            "Counterexample"
+        }
+    }
+}
+
+/// The network models `matrix` compares. None of them changes whether
+/// Agreement or Validity hold; they change how many states it takes to
+/// show it, and what a node has to tolerate on the way.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum NetworkKind {
+    /// FIFO between each pair of nodes: a follower never sees the leader's
+    /// Commit before its Propose. Fewer interleavings but more states, as a
+    /// stale message has to be delivered to get past it, where the
+    /// unordered network can leave it in flight
+    Ordered,
+    /// Any in-flight message can arrive next; the default
+    Unordered,
+    /// Unordered with redelivery, so a node must ignore replayed Votes and
+    /// Commits; the search grows with every copy
+    Duplicating,
+}
+
+impl NetworkKind {
+    pub const ALL: [NetworkKind; 3] = [NetworkKind::Ordered, NetworkKind::Unordered, NetworkKind::Duplicating];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NetworkKind::Ordered => "ordered",
+            NetworkKind::Unordered => "unordered",
+            NetworkKind::Duplicating => "duplicating",
+        }
+    }
+
+    /// `config` on this network instead of its own. Duplication delivers each
+    /// message at most twice, the same as `--network duplicating`.
+    pub fn apply(&self, config: &SimConfig) -> SimConfig {
+        let (ordered, max_dupes) = match self {
+            NetworkKind::Ordered => (true, None),
+            NetworkKind::Unordered => (false, None),
+            NetworkKind::Duplicating => (false, Some(2)),
+        };
+        SimConfig { ordered, max_dupes, ..config.clone() }
+    }
+}
+
+impl std::str::FromStr for NetworkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NetworkKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| format!("unknown network '{}', expected ordered, unordered or duplicating", s))
+    }
+}
+
+/// Checks `config` once per network model, in [`NetworkKind::ALL`] order, so
+/// the results can be compared side by side
+pub fn network_matrix(config: &SimConfig) -> Vec<(NetworkKind, CheckReport)> {
+    NetworkKind::ALL.into_iter().map(|kind| (kind, kind.apply(config).check_report())).collect()
+}
+
+/// Checks `config` once per cluster size in `nodes`, smallest first, keeping
+/// everything else (quorum policy, values, search limits) as configured.
+/// Lazy, so a long sweep can report each size as soon as it's done.
+pub fn size_sweep(
+    config: &SimConfig,
+    nodes: std::ops::RangeInclusive<usize>,
+) -> impl Iterator<Item = (SimConfig, CheckReport)> + '_ {
+    nodes.map(move |n| {
+        let sized = SimConfig { nodes: n, ..config.clone() };
+        let report = sized.check_report();
+        (sized, report)
+    })
+}
+
+/// Column names for [`sweep_csv_row`]: one verdict column per safety
+/// property, then Progress
+pub fn sweep_csv_header() -> String {
+    let mut columns = vec!["nodes", "states_explored", "quorum", "elapsed_secs"];
+    columns.extend(SafetyProperty::ALL.iter().map(|p| p.name()));
+    columns.push("Progress");
+    columns.join(",") + "\n"
+}
+
+/// One line of a `sweep` CSV. Verdicts are `pass`, `fail` or `pending`, and
+/// the time is in seconds with a `.` decimal point whatever the locale, so
+/// rows from different machines can go in the same file.
+pub fn sweep_csv_row(config: &SimConfig, report: &CheckReport) -> String {
+    let verdict = |name: &str| match report.properties.get(name) {
+        Some(Verdict::Pass) => "pass",
+        Some(Verdict::Fail) => "fail",
+        _ => "pending",
+    };
+    let mut cells = vec![
+        config.nodes.to_string(),
+        report.states_explored.to_string(),
+        config.quorum_size().to_string(),
+        format!("{:.3}", report.duration.as_secs_f64()),
+    ];
+    cells.extend(SafetyProperty::ALL.iter().map(|p| verdict(p.name()).to_string()));
+    cells.push(verdict("Progress").to_string());
+    cells.join(",") + "\n"
+}
+
+/// Fluent builder for test scenarios, so each one reads as a description of
+/// the faults rather than model plumbing:
+///
+/// `Scenario::new(3).proposer(0, Value::V0).crash(2).loss(0.1).run_check()`
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    config: SimConfig,
+}
+
+impl Scenario {
+    pub fn new(nodes: usize) -> Self {
+        Scenario {
+            config: SimConfig {
+                nodes,
+                ..SimConfig::default()
+            },
+        }
+    }
+
+    pub fn proposer(mut self, node: usize, value: Value) -> Self {
+        self.config.proposer = node;
+        self.config.proposal = value;
+        self
+    }
+
+    pub fn crash(mut self, node: usize) -> Self {
+        self.config.crashed.push(node);
+        self
+    }
+
+    pub fn partition(mut self, groups: &[&[usize]]) -> Self {
+        self.config.partitions = groups.iter().map(|g| g.to_vec()).collect();
+        self
+    }
+
+    pub fn loss(mut self, rate: f64) -> Self {
+        self.config.loss_rate = rate;
+        self
+    }
+
+    pub fn quorum(mut self, size: usize) -> Self {
+        self.config.quorum = Some(size);
+        self
+    }
+
+    pub fn rival(mut self, node: usize, value: Value) -> Self {
+        self.config.rival = Some((node, value));
+        self
+    }
+
+    pub fn values(mut self, values: &[Value]) -> Self {
+        self.config.values = values.to_vec();
+        self
+    }
+
+    pub fn ordered(mut self) -> Self {
+        self.config.ordered = true;
+        self
+    }
+
+    /// Gives `node` its own idea of the membership, see [`SimConfig::views`]
+    pub fn view(mut self, node: usize, peers: &[usize]) -> Self {
+        self.config.views.insert(node, peers.to_vec());
+        self
+    }
+
+    pub fn search(mut self, search: Search, max_depth: Option<usize>) -> Self {
+        self.config.search = search;
+        self.config.max_depth = max_depth;
+        self
+    }
+
+    pub fn config(&self) -> &SimConfig {
+        &self.config
+    }
+
+    /// The finished config, for [`SimConfig::check_report`] or to save as JSON
+    pub fn build(self) -> SimConfig {
+        self.config
+    }
+
+    /// Panics on an invalid scenario, since that's a bug in the test itself
+    pub fn run_check(&self) -> CheckSummary {
+        if let Err(problems) = self.config.validate() {
+            panic!("invalid scenario: {}", problems.join("; "));
+        }
+        self.config.run_check()
+    }
+}
+
+/// A single message delivery, the unit of a replayable trace
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryRecord {
+    pub src: Id,
+    pub dst: Id,
+    pub msg: ConsensusMsg,
+}
+
+/// Pulls the message deliveries out of a checker discovery, in order.
+/// Drops, timeouts and crashes aren't replayable so they're skipped.
+pub fn deliveries_from_path(path: ConsensusPath) -> Vec<DeliveryRecord> {
+    path.into_actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            ActorModelAction::Deliver { src, dst, msg } => Some(DeliveryRecord { src, dst, msg }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Counts deliveries that didn't change the recipient's state, e.g. a `Vote`
+/// reaching a node that isn't a Candidate. Sends the recipient makes in
+/// response don't count as an effect. Note the checker already skips
+/// deliveries that change nothing *and* send nothing, so on an unordered
+/// network only the former kind can appear in a path.
+pub fn wasted_deliveries(path: ConsensusPath) -> usize {
+    let steps = path.into_vec();
+    steps
+        .windows(2)
+        .filter(|pair| match &pair[0] {
+            (before, Some(ActorModelAction::Deliver { dst, .. })) => {
+                let index = usize::from(*dst);
+                before.actor_states[index] == pair[1].0.actor_states[index]
+            }
+            _ => false,
+        })
+        .count()
+}
+
+/// How many messages of each kind were sent along `path`, including the ones
+/// sent at startup. Reruns the handlers for each step rather than reading the
+/// network, which forgets a message once it's delivered.
+pub fn message_counts(model: &ConsensusModel, path: ConsensusPath) -> BTreeMap<&'static str, usize> {
+    let mut out = Out::new();
+    for (i, actor) in model.actors.iter().enumerate() {
+        actor.on_start(Id::from(i), &mut out);
+    }
+    for (before, action) in path.into_vec() {
+        match action {
+            Some(ActorModelAction::Deliver { src, dst, msg }) => {
+                let mut state = Cow::Borrowed(&*before.actor_states[usize::from(dst)]);
+                model.actors[usize::from(dst)].on_msg(dst, &mut state, src, msg, &mut out);
+            }
+            Some(ActorModelAction::Timeout(id, timer)) => {
+                let mut state = Cow::Borrowed(&*before.actor_states[usize::from(id)]);
+                model.actors[usize::from(id)].on_timeout(id, &mut state, &timer, &mut out);
+            }
+            _ => {}
+        }
+    }
+
+    let mut counts = BTreeMap::new();
+    for command in out {
+        if let Command::Send(_, msg) = command {
+            *counts.entry(msg.kind()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Message deliveries along `path` before any node has decided, or `None`
+/// if nobody decides on it. Drops, timeouts and crashes don't count, so on
+/// the shortest decision path this is a cheap latency measure for comparing
+/// protocol variants.
+pub fn decision_depth(path: ConsensusPath) -> Option<usize> {
+    let mut deliveries = 0;
+    for (state, action) in path.into_vec() {
+        if has_decision(&state.actor_states) {
+            return Some(deliveries);
+        }
+        if let Some(ActorModelAction::Deliver { .. }) = action {
+            deliveries += 1;
+        }
+    }
+    None
+}
+
+/// Per-node view of a witness path as CSV, one row per (step, node) with
+/// step 0 being the initial state. Meant for spreadsheet Gantt charts.
+pub fn decision_timeline_csv(path: ConsensusPath) -> String {
+    let mut csv = String::from("step,node_id,role,proposed,decided\n");
+    let cell = |value: Option<Value>| value.map(|v| v.to_string()).unwrap_or_default();
+    for (step, (state, _)) in path.into_vec().into_iter().enumerate() {
+        for (node, s) in state.actor_states.iter().enumerate() {
+            csv.push_str(&format!(
+                "{},{},{:?},{},{}\n",
+                step,
+                node,
+                s.role,
+                cell(s.proposed_value),
+                cell(s.decided_value)
+            ));
+        }
+    }
+    csv
+}
+
+/// What the checker did to get from one step of a trace to the next
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TraceAction {
+    Deliver(DeliveryRecord),
+    Drop(DeliveryRecord),
+    Timeout { id: Id, timer: Timer },
+    Crash { id: Id },
+}
+
+/// One step of a counterexample: the action taken, and every node's state after it.
+/// The first step is the initial state and has no action.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub action: Option<TraceAction>,
+    pub states: Vec<ConsensusState>,
+}
+
+/// The deliveries of an exported trace, in order, so it can be replayed.
+/// Like [`deliveries_from_path`], everything else is skipped.
+pub fn deliveries_from_trace(steps: &[TraceStep]) -> Vec<DeliveryRecord> {
+    steps
+        .iter()
+        .filter_map(|step| match &step.action {
+            Some(TraceAction::Deliver(d)) => Some(d.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Walks a checker discovery into serializable steps, e.g. for `--trace-out`
+pub fn trace_steps(path: ConsensusPath) -> Vec<TraceStep> {
+    let mut steps = Vec::new();
+    let mut action = None;
+    for (state, next) in path.into_vec() {
+        steps.push(TraceStep {
+            action: action.take(),
+            states: state.actor_states.iter().map(|s| (**s).clone()).collect(),
+        });
+        action = next.map(TraceAction::from);
+    }
+    steps
+}
+
+impl From<ActorModelAction<ConsensusMsg, Timer>> for TraceAction {
+    fn from(action: ActorModelAction<ConsensusMsg, Timer>) -> Self {
+        match action {
+            ActorModelAction::Deliver { src, dst, msg } => TraceAction::Deliver(DeliveryRecord { src, dst, msg }),
+            ActorModelAction::Drop(Envelope { src, dst, msg }) => TraceAction::Drop(DeliveryRecord { src, dst, msg }),
+            ActorModelAction::Timeout(id, timer) => TraceAction::Timeout { id, timer },
+            ActorModelAction::Crash(id) => TraceAction::Crash { id },
+        }
+    }
+}
+
+impl std::fmt::Display for TraceAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceAction::Deliver(d) => write!(f, "{} -> {}: {:?}", usize::from(d.src), usize::from(d.dst), d.msg),
+            TraceAction::Drop(d) => write!(f, "dropped {} -> {}: {:?}", usize::from(d.src), usize::from(d.dst), d.msg),
+            TraceAction::Timeout { id, timer } => write!(f, "timeout at {}: {:?}", usize::from(*id), timer),
+            TraceAction::Crash { id } => write!(f, "crash of {}", usize::from(*id)),
+        }
+    }
+}
+
+/// Length of a discovery and the actions along it, one per line. Under
+/// breadth-first search the discovery is a shortest path, so this is a
+/// minimal counterexample; depth-first search gives just the first found.
+pub fn summarize_discovery(path: &ConsensusPath) -> String {
+    let actions: Vec<TraceAction> = trace_steps(path.clone()).into_iter().filter_map(|step| step.action).collect();
+    let mut out = format!("{} steps\n", actions.len());
+    for (i, action) in actions.iter().enumerate() {
+        out.push_str(&format!("  {}. {}\n", i + 1, action));
+    }
+    out
+}
+
+/// Reachable states and the transitions between them, as collected by
+/// [`explore_state_graph`]. Nodes are labelled with each actor's role and
+/// decision, one actor per line; edges with a short form of the action.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateGraph {
+    pub nodes: Vec<String>,
+    /// (from, to, label), indexing into `nodes`
+    pub edges: Vec<(usize, usize, String)>,
+    /// Whether exploration stopped at the node cap with states left over
+    pub truncated: bool,
+}
+
+/// Breadth-first walk of `model` recording every state and transition, up to
+/// `max_nodes` states. Transitions into states past the cap are dropped, so
+/// a truncated graph is the neighbourhood of the initial state.
+pub fn explore_state_graph(model: &ConsensusModel, max_nodes: usize) -> StateGraph {
+    fn node_label(state: &ConsensusModelState) -> String {
+        let roles: Vec<String> = state
+            .actor_states
+            .iter()
+            .enumerate()
+            .map(|(i, s)| match s.decided_value {
+                Some(value) => format!("{}: {:?} {}", i, s.role, value),
+                None => format!("{}: {:?}", i, s.role),
+            })
+            .collect();
+        roles.join("\n")
+    }
+    fn edge_label(action: &ActorModelAction<ConsensusMsg, Timer>) -> String {
+        match action {
+            ActorModelAction::Deliver { src, dst, msg } => {
+                format!("{} {} -> {}", msg.kind(), usize::from(*src), usize::from(*dst))
+            }
+            ActorModelAction::Drop(Envelope { src, dst, msg }) => {
+                format!("drop {} {} -> {}", msg.kind(), usize::from(*src), usize::from(*dst))
+            }
+            ActorModelAction::Timeout(id, timer) => format!("{:?} timeout at {}", timer, usize::from(*id)),
+            ActorModelAction::Crash(id) => format!("crash of {}", usize::from(*id)),
+        }
+    }
+
+    let mut graph = StateGraph::default();
+    let mut index: HashMap<ConsensusModelState, usize> = HashMap::new();
+    let mut queue = std::collections::VecDeque::new();
+    for state in model.init_states() {
+        if graph.nodes.len() == max_nodes {
+            graph.truncated = true;
+            break;
+        }
+        if !index.contains_key(&state) {
+            index.insert(state.clone(), graph.nodes.len());
+            graph.nodes.push(node_label(&state));
+            queue.push_back(state);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        let from = index[&state];
+        let mut actions = Vec::new();
+        model.actions(&state, &mut actions);
+        for action in actions {
+            let label = edge_label(&action);
+            let Some(next) = model.next_state(&state, action) else { continue };
+            let to = match index.get(&next) {
+                Some(&to) => to,
+                None if graph.nodes.len() == max_nodes => {
+                    graph.truncated = true;
+                    continue;
+                }
+                None => {
+                    let to = graph.nodes.len();
+                    index.insert(next.clone(), to);
+                    graph.nodes.push(node_label(&next));
+                    queue.push_back(next);
+                    to
+                }
+            };
+            graph.edges.push((from, to, label));
+        }
+    }
+    graph
+}
+
+/// Renders `graph` in GraphViz DOT, e.g. for `dot -Tsvg`
+pub fn to_dot(graph: &StateGraph) -> String {
+    let escape = |label: &str| label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    let mut dot = String::from("digraph states {\n    node [shape=box];\n");
+    for (i, label) in graph.nodes.iter().enumerate() {
+        dot.push_str(&format!("    {} [label=\"{}\"];\n", i, escape(label)));
+    }
+    for (from, to, label) in &graph.edges {
+        dot.push_str(&format!("    {} -> {} [label=\"{}\"];\n", from, to, escape(label)));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Which of the enabled actions [`simulate_with`] picks from first; the
+/// seeded choice only happens among those that rank first
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Schedule {
+    /// Every enabled action is equally likely
+    #[default]
+    Random,
+    /// Deliver every Propose in flight before anything else
+    ProposeFirst,
+    /// Hold back Commits while anything else is left to do
+    CommitLast,
+}
+
+impl Schedule {
+    pub const ALL: [Schedule; 3] = [Schedule::Random, Schedule::ProposeFirst, Schedule::CommitLast];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Schedule::Random => "random",
+            Schedule::ProposeFirst => "propose-first",
+            Schedule::CommitLast => "commit-last",
+        }
+    }
+
+    /// Orders two actions by when this schedule wants them, earliest first
+    pub fn compare(
+        &self,
+        a: &ActorModelAction<ConsensusMsg, Timer>,
+        b: &ActorModelAction<ConsensusMsg, Timer>,
+    ) -> std::cmp::Ordering {
+        self.rank(a).cmp(&self.rank(b))
+    }
+
+    fn rank(&self, action: &ActorModelAction<ConsensusMsg, Timer>) -> u8 {
+        let kind = match action {
+            ActorModelAction::Deliver { msg, .. } => Some(msg.kind()),
+            _ => None,
+        };
+        match self {
+            Schedule::Random => 0,
+            Schedule::ProposeFirst => u8::from(kind != Some("Propose")),
+            Schedule::CommitLast => u8::from(kind == Some("Commit")),
+        }
+    }
+}
+
+/// Parses a [`Schedule::name`], as `simulate --schedule` takes it
+impl std::str::FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Schedule::ALL
+            .into_iter()
+            .find(|schedule| schedule.name() == s)
+            .ok_or_else(|| format!("unknown schedule '{}', expected random, propose-first or commit-last", s))
+    }
+}
+
+/// One random run of `model` rather than an exhaustive search: picks the
+/// initial state and then each action with Stateright's [`UniformChooser`]
+/// seeded from `seed`, so the same seed always takes the same run. Logs one
+/// line per action, indented lines for the role and decision changes it
+/// caused, and why the run stopped: every node decided, nothing left to do,
+/// or `max_steps` reached. Nodes with a `drop_vote_prob` lose their Votes
+/// to a second RNG seeded from `seed`, so chaos runs reproduce too.
+pub fn simulate(model: &ConsensusModel, seed: u64, max_steps: usize) -> Vec<String> {
+    simulate_with(model, seed, max_steps, Schedule::Random)
+}
+
+/// Mixed into a run's seed for the RNG that drops Votes, so it doesn't
+/// draw the very numbers the action chooser does
+const CHAOS_SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Same as [`simulate`], choosing only among the actions `schedule` ranks
+/// first at each step
+pub fn simulate_with(model: &ConsensusModel, seed: u64, max_steps: usize, schedule: Schedule) -> Vec<String> {
+    use rand::{Rng, SeedableRng};
+
+    let chooser = UniformChooser;
+    let mut rng = Chooser::<ConsensusModel>::new_state(&chooser, seed);
+    let mut chaos = rand::rngs::StdRng::seed_from_u64(seed ^ CHAOS_SEED_SALT);
+    let init_states = model.init_states();
+    let mut state = init_states[Chooser::<ConsensusModel>::choose_initial_state(&chooser, &mut rng, &init_states)].clone();
+    let mut log = Vec::new();
+
+    for step in 1..=max_steps {
+        if state.actor_states.iter().all(|s| s.decided_value.is_some()) {
+            log.push(format!("all nodes decided after {} steps", step - 1));
+            return log;
+        }
+        let mut actions = Vec::new();
+        model.actions(&state, &mut actions);
+        // Unordered networks make no-op deliveries yield no next state; try
+        // another action instead of counting them as steps, falling back to
+        // lower-ranked ones once the first rank runs out
+        let next = loop {
+            if actions.is_empty() {
+                log.push(format!("no more actions after {} steps", step - 1));
+                return log;
+            }
+            // Stable, so a random schedule leaves the order, and the run, as is
+            actions.sort_by(|a, b| schedule.compare(a, b));
+            let first = actions.partition_point(|a| schedule.compare(a, &actions[0]).is_eq());
+            let chosen = Chooser::<ConsensusModel>::choose_action(&chooser, &mut rng, &state, &actions[..first]);
+            let action = actions.swap_remove(chosen);
+            if let Some(next) = model.next_state(&state, action.clone()) {
+                break (action, next);
+            }
+        };
+        let (action, mut next) = next;
+        log.push(format!("{}. {}", step, TraceAction::from(action.clone())));
+        if let ActorModelAction::Deliver { dst, .. } = action {
+            let p = model.actors[usize::from(dst)].drop_vote_prob;
+            for vote in new_votes(&state, &next, dst) {
+                if p > 0.0 && chaos.gen_bool(p) {
+                    log.push(format!("    node {} never sent its {:?} to {}", usize::from(dst), vote.msg, usize::from(vote.dst)));
+                    next = model.next_state(&next, ActorModelAction::Drop(vote)).expect("the vote is in flight");
+                }
+            }
+        }
+        for (node, (before, after)) in state.actor_states.iter().zip(&next.actor_states).enumerate() {
+            if before.role != after.role {
+                log.push(format!("    node {} role: {:?} -> {:?}", node, before.role, after.role));
+            }
+            if before.decided_value != after.decided_value {
+                log.push(format!("    node {} decided: {:?} -> {:?}", node, before.decided_value, after.decided_value));
+            }
+        }
+        state = next;
+    }
+    log.push(format!("stopped at the {} step cap", max_steps));
+    log
+}
+
+/// Votes from `voter` in flight in `after` but not in `before`, one per copy
+fn new_votes(before: &ConsensusModelState, after: &ConsensusModelState, voter: Id) -> Vec<Envelope<ConsensusMsg>> {
+    let mut votes: Vec<Envelope<ConsensusMsg>> = after
+        .network
+        .iter_all()
+        .filter(|env| env.src == voter && env.msg.kind() == "Vote")
+        .map(|env| env.to_cloned_msg())
+        .collect();
+    for env in before.network.iter_all() {
+        if let Some(i) = votes.iter().position(|vote| *vote == env.to_cloned_msg()) {
+            votes.remove(i);
+        }
+    }
+    votes
+}
+
+/// Re-drives fresh actors built from `config` through exactly the given deliveries.
+/// Messages the actors send in response are discarded - only the listed
+/// deliveries happen, so a trace reproduces the same states every time.
+pub fn replay(config: &SimConfig, deliveries: &[DeliveryRecord]) -> Vec<std::sync::Arc<ConsensusState>> {
+    replay_with(config, deliveries, |_, _, _| {})
+}
+
+/// [`replay`] for a bare trace, e.g. one loaded from JSON to reproduce a
+/// bug report. There's no config to go with it, so this uses
+/// [`SimConfig::for_trace`].
+pub fn replay_trace(trace: &[DeliveryRecord]) -> Vec<ConsensusState> {
+    replay(&SimConfig::for_trace(trace), trace)
+        .into_iter()
+        .map(|state| (*state).clone())
+        .collect()
+}
+
+/// Same as [`replay`], calling `on_step` with each delivery and the
+/// recipient's state before and after it
+fn replay_with(
+    config: &SimConfig,
+    deliveries: &[DeliveryRecord],
+    mut on_step: impl FnMut(&DeliveryRecord, &ConsensusState, &ConsensusState),
+) -> Vec<std::sync::Arc<ConsensusState>> {
+    let actors = config.actors();
+    let mut states: Vec<ConsensusState> = actors
+        .iter()
+        .enumerate()
+        .map(|(i, actor)| actor.on_start(Id::from(i), &mut Out::new()))
+        .collect();
+
+    for d in deliveries {
+        let index = usize::from(d.dst);
+        let (next, _) = actors[index].step(d.dst, &states[index], d.src, &d.msg);
+        on_step(d, &states[index], &next);
+        states[index] = next;
+    }
+
+    states.into_iter().map(std::sync::Arc::new).collect()
+}
+
+/// Field-level differences from `a` to `b`, e.g. `role: Follower -> Candidate`
+/// or `votes: +{1}`. Empty when the states are equal.
+pub fn diff_states(a: &ConsensusState, b: &ConsensusState) -> Vec<String> {
+    fn set_change<T: Ord + Debug>(name: &str, added: Vec<T>, removed: Vec<T>) -> Option<String> {
+        let list = |items: &[T]| items.iter().map(|i| format!("{:?}", i)).collect::<Vec<_>>().join(", ");
+        match (added.is_empty(), removed.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(format!("{}: +{{{}}}", name, list(&added))),
+            (true, false) => Some(format!("{}: -{{{}}}", name, list(&removed))),
+            (false, false) => Some(format!("{}: +{{{}}} -{{{}}}", name, list(&added), list(&removed))),
+        }
+    }
+    let voters = |s: &ConsensusState| {
+        s.votes_received.values().flatten().map(|&id| usize::from(id)).collect::<BTreeSet<_>>()
+    };
+    let (votes_a, votes_b) = (voters(a), voters(b));
+
+    let mut diffs = Vec::new();
+    if a.role != b.role {
+        diffs.push(format!("role: {:?} -> {:?}", a.role, b.role));
+    }
+    if a.proposed_value != b.proposed_value {
+        diffs.push(format!("proposed: {:?} -> {:?}", a.proposed_value, b.proposed_value));
+    }
+    diffs.extend(set_change(
+        "votes",
+        votes_b.difference(&votes_a).copied().collect(),
+        votes_a.difference(&votes_b).copied().collect(),
+    ));
+    if a.decided_value != b.decided_value {
+        diffs.push(format!("decided: {:?} -> {:?}", a.decided_value, b.decided_value));
+    }
+    if a.term != b.term {
+        diffs.push(format!("term: {} -> {}", a.term, b.term));
+    }
+    if a.slot != b.slot {
+        diffs.push(format!("slot: {} -> {}", a.slot, b.slot));
+    }
+    if a.decided_log != b.decided_log {
+        diffs.push(format!("log: {:?} -> {:?}", a.decided_log, b.decided_log));
+    }
+    let vote_terms = |x: &ConsensusState, y: &ConsensusState| -> Vec<(usize, Value, u64)> {
+        x.vote_terms.difference(&y.vote_terms).map(|&(voter, value, term)| (usize::from(voter), value, term)).collect()
+    };
+    diffs.extend(set_change("vote terms", vote_terms(b, a), vote_terms(a, b)));
+    diffs.extend(set_change(
+        "certificate",
+        b.certificate.difference(&a.certificate).map(|&id| usize::from(id)).collect(),
+        a.certificate.difference(&b.certificate).map(|&id| usize::from(id)).collect(),
+    ));
+    diffs.extend(set_change(
+        "commit acks",
+        b.commit_acks.difference(&a.commit_acks).map(|&id| usize::from(id)).collect(),
+        a.commit_acks.difference(&b.commit_acks).map(|&id| usize::from(id)).collect(),
+    ));
+    diffs
+}
+
+/// Human-readable walk through a trace: one line per delivery, followed by
+/// what it changed on the recipient
+pub fn render_trace(config: &SimConfig, deliveries: &[DeliveryRecord]) -> String {
+    let mut out = String::new();
+    replay_with(config, deliveries, |d, before, after| {
+        out.push_str(&format!("{} -> {}: {:?}\n", usize::from(d.src), usize::from(d.dst), d.msg));
+        let diffs = diff_states(before, after);
+        if diffs.is_empty() {
+            out.push_str("    (no change)\n");
+        }
+        for diff in diffs {
+            out.push_str(&format!("    node {} {}\n", usize::from(d.dst), diff));
+        }
+    });
+    out
+}
+
+/// Generates a standalone `#[test]` that replays `deliveries` and asserts the
+/// named safety property is violated. Returns `None` for properties that
+/// can't be evaluated on a single state (e.g. Progress).
+pub fn render_repro_test(config: &SimConfig, property: &str, deliveries: &[DeliveryRecord]) -> Option<String> {
+    let property = SafetyProperty::from_name(property)?;
+    let config_json = serde_json::to_string(config).expect("config serializes");
+    let deliveries_json = serde_json::to_string_pretty(deliveries).expect("deliveries serialize");
+    let fn_name = format!("repro_{}", property.name().to_lowercase());
+
+    Some(format!(
+        r####"// Generated by `consensus check --emit-repro` - violation of {property:?}
+
+use consensus_stateright::*;
+
+#[test]
+fn {fn_name}() {{
+    let config = SimConfig::from_json(r#"{config_json}"#).unwrap();
+    let deliveries: Vec<DeliveryRecord> = serde_json::from_str(
+        r#"{deliveries_json}"#,
+    )
+    .unwrap();
+
+    let states = replay(&config, &deliveries);
+    assert!(!SafetyProperty::{property:?}.eval(&config.model_config(), &states), "expected {property:?} to be violated");
+}}
+"####
+    ))
+}
+
+/// Checks that the explorer can actually bind `address` before handing it to
+/// Stateright, which panics deep inside its HTTP server when the port is taken.
+pub fn validate_explorer_address(address: &str) -> Result<std::net::SocketAddr, String> {
+    use std::net::ToSocketAddrs;
+    let addr = address
+        .to_socket_addrs()
+        .map_err(|e| format!("invalid address {}: {}", address, e))?
+        .next()
+        .ok_or_else(|| format!("address {} did not resolve", address))?;
+    std::net::TcpListener::bind(addr).map_err(|e| format!("cannot bind {}: {}", addr, e))?;
+    Ok(addr)
+}
+
+/// Builds a shareable explorer link that opens directly on a specific state.
+///
+/// `initial_path` lists which transition to take at each step, starting from
+/// the initial state: index `i` picks the i-th enabled transition in the
+/// order the explorer lists them (0-based). `[]` is the initial state itself,
+/// `[1, 0]` takes the second transition and then the first one after that.
+/// The link encodes the state fingerprints along that path, which is the
+/// format the explorer's `#/steps/...` route expects.
+pub fn explorer_url<M>(model: &M, address: std::net::SocketAddr, initial_path: &[usize]) -> Result<String, String>
+where
+    M: Model,
+    M::State: Clone + Hash + PartialEq,
+    M::Action: PartialEq,
+{
+    let init = model
+        .init_states()
+        .into_iter()
+        .next()
+        .ok_or("model has no initial state")?;
+
+    let mut state = init.clone();
+    let mut actions = Vec::with_capacity(initial_path.len());
+    for (step, &index) in initial_path.iter().enumerate() {
+        let mut steps = model.next_steps(&state);
+        if index >= steps.len() {
+            return Err(format!(
+                "step {}: transition {} requested but only {} available",
+                step,
+                index,
+                steps.len()
+            ));
+        }
+        let (action, next) = steps.swap_remove(index);
+        actions.push(action);
+        state = next;
+    }
+
+    let path = Path::from_actions(model, init, &actions).ok_or("path is not reachable")?;
+    let host = if address.ip().is_unspecified() {
+        format!("localhost:{}", address.port())
+    } else {
+        address.to_string()
+    };
+    Ok(format!("http://{}/#/steps/{}", host, path.encode()))
+}
+
+/// Like `checker().serve(..)`, but validates the address first and prints a
+/// link to the state reached by `initial_path` (see [`explorer_url`]).
+/// Blocks serving the UI once started.
+pub fn explore_with_initial<M>(model: M, address: &str, initial_path: &[usize]) -> Result<(), String>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Clone + Debug + Hash + PartialEq + Send + Sync,
+    M::Action: Debug + PartialEq + Send + Sync,
+{
+    let addr = validate_explorer_address(address)?;
+    let url = explorer_url(&model, addr, initial_path)?;
+    println!("Opening web UI at {}", url);
+    model.checker().serve(addr);
+    Ok(())
+}
+
+/// Order the checker explores states in
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Search {
+    #[default]
+    Bfs,
+    Dfs,
+}
+
+impl Search {
+    pub fn name(self) -> &'static str {
+        match self {
+            Search::Bfs => "breadth-first",
+            Search::Dfs => "depth-first",
+        }
+    }
+}
+
+/// Checker threads per search unless asked otherwise
+// 4 threads seems optimal on my laptop. tried 8 but didn't help
+// much, probably memory bound not CPU bound
+pub const DEFAULT_THREADS: usize = 4;
+
+/// Runs the checker over `model` to completion with the given strategy and
+/// number of threads, stopping at `max_depth` steps from the initial states
+/// if set so even an unbounded model terminates. `max_states` stops it once
+/// that many unique states were seen; the threads only check between
+/// batches, so it can overshoot by a few thousand, and anything past the cap
+/// goes unchecked, see [`hit_state_cap`].
+pub fn run_search<M>(
+    model: M,
+    search: Search,
+    max_depth: Option<usize>,
+    max_states: Option<usize>,
+    threads: usize,
+) -> Box<dyn Checker<M>>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash + Send + Sync + 'static,
+{
+    let mut builder = model.checker().threads(threads);
+    if let Some(depth) = max_depth {
+        builder = builder.target_max_depth(depth);
+    }
+    if let Some(count) = max_states {
+        builder = builder.target_state_count(count);
+    }
+    match search {
+        Search::Bfs => Box::new(builder.spawn_bfs().join()),
+        Search::Dfs => Box::new(builder.spawn_dfs().join()),
+    }
+}
+
+/// Like [`run_search`] with a depth-first search, but states that only
+/// differ by a renaming of node ids are explored once. Only sound when the
+/// nodes are interchangeable, see [`SimConfig::is_symmetric`]. Stateright
+/// only applies the reduction to depth-first searches, and picks the
+/// representative by sorting nodes by state, so nodes in identical states
+/// keep their order and some renamings still get explored separately.
+pub fn run_symmetric_search<M>(
+    model: M,
+    max_depth: Option<usize>,
+    max_states: Option<usize>,
+    threads: usize,
+) -> Box<dyn Checker<M>>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash + Representative + Send + Sync + 'static,
+{
+    let mut builder = model.checker().threads(threads).symmetry();
+    if let Some(depth) = max_depth {
+        builder = builder.target_max_depth(depth);
+    }
+    if let Some(count) = max_states {
+        builder = builder.target_state_count(count);
+    }
+    Box::new(builder.spawn_dfs().join())
+}
+
+/// Whether a checker run with `max_states` may have stopped at the cap
+/// rather than running out of states. Errs towards "stopped": the checker
+/// overshoots the cap, so a run that finishes just past it looks the same
+/// as one that was cut off.
+pub fn hit_state_cap(states_explored: usize, max_states: Option<usize>) -> bool {
+    max_states.is_some_and(|max| states_explored >= max)
+}
+
+/// Whether a checker run with `max_depth` may have left states unexplored
+/// below the bound. The checker skips the successors of anything at the
+/// bound without saying whether there were any, so reaching it counts.
+/// Stateright takes a bound of 0 as no bound at all.
+pub fn hit_depth_bound(depth_reached: usize, max_depth: Option<usize>) -> bool {
+    max_depth.is_some_and(|max| max > 0 && depth_reached >= max)
+}
+
+// Helper functions for checking properties
+// These get used by the model checker in main.rs
+
+/// Anything a node can decide with, so the properties below work for every
+/// protocol in the crate
+pub trait Decision {
+    type Value: Copy + Eq;
+    fn decided_value(&self) -> Option<Self::Value>;
+}
+
+impl<V: Copy + Eq + Hash> Decision for ConsensusState<V> {
+    type Value = V;
+    fn decided_value(&self) -> Option<V> {
+        self.decided_value
+    }
+}
+
+pub fn check_agreement<S: Decision>(states: &[std::sync::Arc<S>]) -> bool {
+    all_same(states.iter().filter_map(|s| s.decided_value()))
+}
+
+/// Agreement among the `honest` node indices only, for runs with Byzantine
+/// nodes: whatever a faulty node claims to have decided doesn't count
+pub fn check_agreement_among<S: Decision>(states: &[std::sync::Arc<S>], honest: &HashSet<usize>) -> bool {
+    all_same(
+        states
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| honest.contains(i))
+            .filter_map(|(_, s)| s.decided_value()),
+    )
+}
+
+/// Agreement: all nodes that decide must decide the same value, trivially
+/// true if 0 or 1 node decided
+fn all_same<V: PartialEq>(mut decided: impl Iterator<Item = V>) -> bool {
+    match decided.next() {
+        Some(first) => decided.all(|v| v == first),
+        None => true,
+    }
+}
+
+/// Agreement slot by slot: wherever two nodes have both decided a log slot,
+/// they decided the same value there
+pub fn check_log_agreement<V: Copy + Eq + Hash>(states: &[std::sync::Arc<ConsensusState<V>>]) -> bool {
+    let longest = states.iter().map(|s| s.decided_log.len()).max().unwrap_or(0);
+    (0..longest).all(|slot| {
+        let mut decided = states.iter().filter_map(|s| s.decided_log.get(slot));
+        let first = decided.next();
+        decided.all(|v| Some(v) == first)
+    })
+}
+
+/// No node aborted while some value could still gather a quorum of
+/// `quorum` nodes: those that decided or back it, counting Aborted nodes for
+/// the value they gave up on, plus every Follower yet to back anything. Over-
+/// approximates, since a quorum's votes must also go to a single Candidate.
+pub fn check_no_spurious_abort<V: Copy + Eq + Hash>(states: &[std::sync::Arc<ConsensusState<V>>], quorum: usize) -> bool {
+    if states.iter().all(|s| s.role != NodeRole::Aborted) {
+        return true;
+    }
+    let free = states
+        .iter()
+        .filter(|s| s.role == NodeRole::Follower && s.proposed_value.is_none() && s.decided_value.is_none())
+        .count();
+    let mut backers: HashMap<V, usize> = HashMap::new();
+    for s in states {
+        if let Some(value) = s.decided_value.or(s.proposed_value) {
+            *backers.entry(value).or_default() += 1;
+        }
+    }
+    backers.values().all(|&count| count + free < quorum)
+}
+
+/// Every decision must be one of `values`, the domain this run may decide
+pub fn check_validity<S: Decision>(states: &[std::sync::Arc<S>], values: &[S::Value]) -> bool {
+    states
+        .iter()
+        .all(|s| s.decided_value().is_none_or(|v| values.contains(&v)))
+}
+
+/// At most one Leader per term. Relies on terms: two Leaders in different
+/// terms are just a stale one that hasn't heard of the newer round yet.
+pub fn check_single_leader_per_term(states: &[std::sync::Arc<ConsensusState>]) -> bool {
+    let mut terms = HashSet::new();
+    states
+        .iter()
+        .filter(|s| s.role == NodeRole::Leader)
+        .all(|s| terms.insert(s.term))
+}
+
+/// Every decision must be a value some node in this run is actually
+/// proposing, judged from the states alone rather than the configuration
+pub fn check_integrity(states: &[std::sync::Arc<ConsensusState>]) -> bool {
+    let proposed: HashSet<Value> = states.iter().filter_map(|s| s.proposed_value).collect();
+    states
+        .iter()
+        .all(|s| s.decided_value.is_none_or(|v| proposed.contains(&v)))
+}
+
+/// Decisions are final: along a path's states, once a node has decided a
+/// value it keeps that value and its role (Decided, or Learner for
+/// learners). This compares states with their successors, so it can't be an
+/// `Always` property; run it on `path.into_states()`, e.g. from a checker
+/// visitor to cover every path.
+pub fn check_decision_stability(states: &[ConsensusModelState]) -> bool {
+    states.windows(2).all(|pair| {
+        pair[0].actor_states.iter().zip(&pair[1].actor_states).all(|(before, after)| {
+            before
+                .decided_value
+                .is_none_or(|v| after.decided_value == Some(v) && after.role == before.role)
+        })
+    })
+}
+
+/// Values some actor in `model` starts out proposing
+pub fn proposed_values<C, H: Clone + Debug + Hash>(model: &ActorModel<ConsensusActor, C, H>) -> Vec<Value> {
+    model
+        .actors
+        .iter()
+        .enumerate()
+        .filter_map(|(i, actor)| actor.initial_proposal(Id::from(i)))
+        .collect()
+}
+
+pub fn check_total_votes_bound(states: &[std::sync::Arc<ConsensusState>], n: usize) -> bool {
+    // Each node can hold at most one vote per peer, so the cluster-wide total
+    // can never exceed n * n. Anything above that means votes are leaking.
+    let total: usize = states
+        .iter()
+        .flat_map(|s| s.votes_received.values())
+        .map(HashSet::len)
+        .sum();
+    total <= n * n
+}
+
+/// A node that led commits what it campaigned for: wherever its own tally
+/// reached `quorum` votes for a value, that value is its `proposed_value`,
+/// and it's the only one it commits as Leader or decides. Integrity only
+/// asks that *someone* proposed the decided value; this pins a leader's
+/// decision to its own bid. Counts heads; see
+/// [`check_leader_commits_proposal_with`] for stake.
+pub fn check_leader_commits_proposal<V: Copy + Eq + Hash>(
+    states: &[std::sync::Arc<ConsensusState<V>>],
+    quorum: usize,
+) -> bool {
+    check_leader_commits_proposal_with(states, |voters| voters.len() >= quorum)
+}
+
+/// [`check_leader_commits_proposal`] with `is_quorum` deciding which tallies
+/// count, e.g. [`ModelConfig::is_quorum`] to weigh them by stake
+pub fn check_leader_commits_proposal_with<V: Copy + Eq + Hash>(
+    states: &[std::sync::Arc<ConsensusState<V>>],
+    is_quorum: impl Fn(&HashSet<Id>) -> bool,
+) -> bool {
+    states
+        .iter()
+        .filter(|s| matches!(s.role, NodeRole::Leader | NodeRole::Decided))
+        .all(|s| {
+            let committing = match &s.pending_commit {
+                Some(ConsensusMsg::Commit { value, .. }) => Some(*value),
+                _ => None,
+            };
+            s.votes_received.iter().filter(|(_, voters)| is_quorum(voters)).all(|(&led, _)| {
+                s.proposed_value == Some(led)
+                    && s.decided_value.is_none_or(|v| v == led)
+                    && committing.is_none_or(|v| v == led)
+            })
+        })
+}
+
+/// No node has seen a Commit contradict its decision. Agreement compares
+/// decisions; this fires as soon as a conflicting Commit lands, and the
+/// counterexample ends at the node that got it.
+pub fn check_no_safety_trap<V: Eq + Hash>(states: &[std::sync::Arc<ConsensusState<V>>]) -> bool {
+    states.iter().all(|s| !s.safety_violation)
+}
+
+/// Every vote in a node's tally, and so in any quorum it decides on, was
+/// cast in the view the node is in. `vote_terms` outlives view changes, so
+/// a tally that kept a voter across one fails unless that voter voted the
+/// same way again in the new view.
+pub fn check_same_view_quorum<V: Copy + Hash + Ord>(states: &[std::sync::Arc<ConsensusState<V>>]) -> bool {
+    states.iter().all(|s| {
+        s.votes_received
+            .iter()
+            .all(|(&value, voters)| voters.iter().all(|&voter| s.vote_terms.contains(&(voter, value, s.term))))
+    })
+}
+
+/// The safety invariants as values, so callers can iterate, filter and attach
+/// them uniformly instead of spelling out a closure per property
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SafetyProperty {
+    Agreement,
+    Validity,
+    TotalVotesBound,
+    SameViewQuorum,
+    Integrity,
+    SingleLeaderPerTerm,
+    LeaderCommitsProposal,
+    NoSafetyTrap,
+    CrashedNeverVote,
+}
+
+impl SafetyProperty {
+    pub const ALL: [SafetyProperty; 9] = [
+        SafetyProperty::Agreement,
+        SafetyProperty::Validity,
+        SafetyProperty::TotalVotesBound,
+        SafetyProperty::SameViewQuorum,
+        SafetyProperty::Integrity,
+        SafetyProperty::SingleLeaderPerTerm,
+        SafetyProperty::LeaderCommitsProposal,
+        SafetyProperty::NoSafetyTrap,
+        SafetyProperty::CrashedNeverVote,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SafetyProperty::Agreement => "Agreement",
+            SafetyProperty::Validity => "Validity",
+            SafetyProperty::TotalVotesBound => "TotalVotesBound",
+            SafetyProperty::SameViewQuorum => "SameViewQuorum",
+            SafetyProperty::Integrity => "Integrity",
+            SafetyProperty::SingleLeaderPerTerm => "SingleLeaderPerTerm",
+            SafetyProperty::LeaderCommitsProposal => "LeaderCommitsProposal",
+            SafetyProperty::NoSafetyTrap => "NoSafetyTrap",
+            SafetyProperty::CrashedNeverVote => "CrashedNeverVote",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    /// Evaluates the invariant on one global state of the cluster in `cfg`;
+    /// one actor state per node.
+    pub fn eval(&self, cfg: &ModelConfig, states: &[std::sync::Arc<ConsensusState>]) -> bool {
+        match self {
+            SafetyProperty::Agreement => check_agreement(states) && check_log_agreement(states),
+            SafetyProperty::Validity => check_validity(states, &cfg.values),
+            SafetyProperty::TotalVotesBound => check_total_votes_bound(states, states.len()),
+            SafetyProperty::SameViewQuorum => check_same_view_quorum(states),
+            SafetyProperty::Integrity => check_integrity(states),
+            SafetyProperty::SingleLeaderPerTerm => check_single_leader_per_term(states),
+            SafetyProperty::LeaderCommitsProposal => check_leader_commits_proposal_with(states, |voters| cfg.is_quorum(voters)),
+            SafetyProperty::NoSafetyTrap => check_no_safety_trap(states),
+            SafetyProperty::CrashedNeverVote => {
+                let crashed: Vec<Id> = cfg.crashed.iter().copied().map(Id::from).collect();
+                check_crashed_never_vote(states, &crashed)
+            }
+        }
+    }
+
+    /// Model properties need a plain `fn`, so each variant gets its own
+    /// non-capturing closure
+    pub fn condition(&self) -> fn(&ConsensusModel, &ConsensusModelState) -> bool {
+        match self {
+            SafetyProperty::Agreement => |m, s| SafetyProperty::Agreement.eval(&m.cfg, &s.actor_states),
+            SafetyProperty::Validity => |m, s| SafetyProperty::Validity.eval(&m.cfg, &s.actor_states),
+            SafetyProperty::TotalVotesBound => |m, s| SafetyProperty::TotalVotesBound.eval(&m.cfg, &s.actor_states),
+            SafetyProperty::SameViewQuorum => |m, s| SafetyProperty::SameViewQuorum.eval(&m.cfg, &s.actor_states),
+            SafetyProperty::Integrity => |m, s| SafetyProperty::Integrity.eval(&m.cfg, &s.actor_states),
+            SafetyProperty::SingleLeaderPerTerm => |m, s| SafetyProperty::SingleLeaderPerTerm.eval(&m.cfg, &s.actor_states),
+            SafetyProperty::LeaderCommitsProposal => {
+                |m, s| SafetyProperty::LeaderCommitsProposal.eval(&m.cfg, &s.actor_states)
+            }
+            SafetyProperty::NoSafetyTrap => |m, s| SafetyProperty::NoSafetyTrap.eval(&m.cfg, &s.actor_states),
+            // From the actors, which a hand-built model's `cfg` may not list
+            SafetyProperty::CrashedNeverVote => |model, s| check_crashed_never_vote(&s.actor_states, &crashed_ids(model)),
+        }
+    }
+}
+
+/// Leads-to check from "some node is Leader" to "some node decided", meant
+/// for `Expectation::Eventually`. A run satisfies it once someone decides, or
+/// if it goes quiet without ever electing a Leader; the only way to fail is
+/// to end with a Leader and no decision. Assumes a non-duplicating network,
+/// where "quiet" means nothing left in flight.
+pub fn leader_leads_to_decision(_: &ConsensusModel, state: &ConsensusModelState) -> bool {
+    let leader = state.actor_states.iter().any(|s| s.role == NodeRole::Leader);
+    has_decision(&state.actor_states) || (!leader && state.network.len() == 0)
+}
+
+/// How many nodes decided each value, with every value listed, e.g.
+/// `{V0: 2, V1: 0, V2: 1}`. A healthy run has all its decisions on one value.
+pub fn decision_histogram(states: &[std::sync::Arc<ConsensusState>]) -> BTreeMap<Value, usize> {
+    let mut histogram: BTreeMap<Value, usize> = Value::ALL.iter().map(|&v| (v, 0)).collect();
+    for value in states.iter().filter_map(|s| s.decided_value) {
+        *histogram.entry(value).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Whether every node in `states` has decided
+pub fn all_decided(states: &[std::sync::Arc<ConsensusState>]) -> bool {
+    states.iter().all(|s| s.decided_value.is_some())
+}
+
+/// Termination, for `Expectation::Eventually`: every run ends with all
+/// non-crashed nodes decided. Only meaningful on a reliable network - with
+/// loss, a dropped Propose or Commit strands the run forever, which is the
+/// FLP impossibility showing up rather than a bug.
+pub fn termination(model: &ConsensusModel, state: &ConsensusModelState) -> bool {
+    let live: Vec<_> = state
+        .actor_states
+        .iter()
+        .zip(&model.actors)
+        .filter(|(_, actor)| !actor.crashed)
+        .map(|(s, _)| s.clone())
+        .collect();
+    all_decided(&live)
+}
+
+/// Whether the run has ended in `state`: no action leads to a new state
+/// within the model's boundary
+pub fn is_terminal(model: &ConsensusModel, state: &ConsensusModelState) -> bool {
+    let mut actions = Vec::new();
+    model.actions(state, &mut actions);
+    actions
+        .into_iter()
+        .filter_map(|action| model.next_state(state, action))
+        .all(|next| !model.within_boundary(&next))
+}
+
+/// Shortest path to a run that has ended with a live node undecided, i.e. a
+/// Termination counterexample. Stateright's own `Eventually` discoveries can
+/// end past the state that broke the property, but decisions are final, so
+/// any path to such a state is a counterexample.
+pub fn find_stuck_run(model: ConsensusModel) -> Option<ConsensusPath> {
+    model
+        .property(Expectation::Always, "NotStuck", |model, state| {
+            termination(model, state) || !is_terminal(model, state)
+        })
+        .checker()
+        .spawn_bfs()
+        .join()
+        .discovery("NotStuck")
+}
+
+/// Ids of the actors configured as crashed in `model`
+pub fn crashed_ids(model: &ConsensusModel) -> Vec<Id> {
+    model
+        .actors
+        .iter()
+        .enumerate()
+        .filter(|(_, actor)| actor.crashed)
+        .map(|(i, _)| Id::from(i))
+        .collect()
+}
+
+/// A named property check, as [`ActorModel::property`] takes it
+pub type PropertySpec = (Expectation, &'static str, fn(&ConsensusModel, &ConsensusModelState) -> bool);
+
+/// Everything [`SimConfig::build_model`] checks: the safety properties,
+/// Progress and ConsensusReached
+pub fn standard_properties() -> Vec<PropertySpec> {
+    let mut properties: Vec<PropertySpec> = SafetyProperty::ALL
+        .into_iter()
+        .map(|property| (Expectation::Always, property.name(), property.condition()))
+        .collect();
+    properties.push((Expectation::Sometimes, "Progress", |_, state| has_decision(&state.actor_states)));
+    properties.push((Expectation::Sometimes, "ConsensusReached", |model, state| {
+        consensus_reached(&state.actor_states, model.cfg.quorum).is_some()
+    }));
+    properties
+}
+
+/// The opt-in `Eventually` checks, which only pass on reliable networks or
+/// fair schedules
+pub fn liveness_properties() -> Vec<PropertySpec> {
+    vec![
+        (Expectation::Eventually, "LeaderLeadsToDecision", leader_leads_to_decision),
+        (Expectation::Eventually, "Termination", termination),
+    ]
+}
+
+/// Attaches each of `properties` to `model`
+pub fn with_properties(model: ConsensusModel, properties: impl IntoIterator<Item = PropertySpec>) -> ConsensusModel {
+    properties
+        .into_iter()
+        .fold(model, |model, (expectation, name, condition)| model.property(expectation, name, condition))
+}
+
+/// The default cluster of `node_count` nodes with node 0 proposing, as
+/// checked by `check` and shown by `explore`. `include_liveness` adds the
+/// LeaderLeadsToDecision and Termination properties on top of the safety
+/// ones and Progress.
+pub fn build_model(node_count: usize, include_liveness: bool) -> ConsensusModel {
+    let options = ModelOptions {
+        nodes: node_count,
+        fair: include_liveness,
+        check_liveness: include_liveness,
+        ..ModelOptions::default()
+    };
+    options.model(&options.config())
+}
+
+/// What shapes the models `check` searches and `explore` shows, as their
+/// flags give it. Both build through here, so the two can't drift apart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelOptions {
+    pub nodes: usize,
+    pub network: NetworkKind,
+    /// Deliveries per message on a duplicating network, instead of the
+    /// two [`NetworkKind::apply`] allows
+    pub max_dupes: Option<usize>,
+    /// Chance of dropping a message, 0 to 1; only zero versus nonzero matters
+    pub loss_rate: f64,
+    /// Size of the value domain, 1 to 3
+    pub values: usize,
+    /// Fixes node 0's proposal; only the rival's value varies then
+    pub propose: Option<Value>,
+    /// Pit a rival against every proposal, see [`SimConfig::contention`]
+    pub contention: bool,
+    /// Every node campaigns for a different value at start
+    pub all_propose: bool,
+    /// Judge liveness only on fair schedules, which never lose a message,
+    /// and on those also check that every Leader leads to a decision
+    pub fair: bool,
+    /// Add the Termination check; only expected to pass without message
+    /// loss, or with `fair`
+    pub check_liveness: bool,
+    /// The network may withhold one message for good
+    pub adversary: bool,
+}
+
+impl Default for ModelOptions {
+    fn default() -> Self {
+        ModelOptions {
+            nodes: 3,
+            network: NetworkKind::Unordered,
+            max_dupes: None,
+            loss_rate: 0.0,
+            values: Value::ALL.len(),
+            propose: None,
+            contention: false,
+            all_propose: false,
+            fair: false,
+            check_liveness: false,
+            adversary: false,
+        }
+    }
+}
+
+impl ModelOptions {
+    /// The base config the scenarios are built from, not yet validated
+    pub fn config(&self) -> SimConfig {
+        let config = self.network.apply(&SimConfig {
+            nodes: self.nodes,
+            values: Value::domain(self.values),
+            loss_rate: self.loss_rate,
+            proposal: self.propose.unwrap_or_default(),
+            contention: self.contention,
+            all_propose: self.all_propose,
+            adversary: self.adversary,
+            ..SimConfig::default()
+        });
+        match self.max_dupes {
+            Some(k) if self.network == NetworkKind::Duplicating => SimConfig { max_dupes: Some(k), ..config },
+            _ => config,
+        }
+    }
+
+    /// `config`'s scenarios, keeping only those where the proposer
+    /// campaigns for `propose` if it's set
+    pub fn scenarios(&self, config: &SimConfig) -> Vec<SimConfig> {
+        let mut scenarios = config.scenarios();
+        scenarios.retain(|s| self.propose.is_none_or(|value| s.proposal == value));
+        scenarios
+    }
+
+    /// The liveness properties these options ask for
+    pub fn liveness(&self) -> impl Iterator<Item = PropertySpec> + '_ {
+        liveness_properties().into_iter().filter(|&(_, name, _)| match name {
+            "LeaderLeadsToDecision" => self.fair,
+            "Termination" => self.check_liveness,
+            _ => false,
+        })
+    }
+
+    /// `scenario`'s model with the standard properties and the liveness
+    /// ones asked for, checked on every schedule alike
+    pub fn model(&self, scenario: &SimConfig) -> ConsensusModel {
+        with_properties(scenario.build_model(), self.liveness())
+    }
+
+    /// The models `check` searches for `scenario`: one for safety, and under
+    /// `fair` a second one for liveness on just the fair schedules, unless
+    /// every schedule is fair anyway. Without a second model liveness is
+    /// judged on the first.
+    pub fn scenario_models(&self, scenario: &SimConfig) -> (ConsensusModel, Option<ConsensusModel>) {
+        let fair = scenario.fair_schedules();
+        if self.fair && fair != *scenario {
+            (scenario.build_model(), Some(self.model(&fair)))
+        } else {
+            (self.model(scenario), None)
+        }
+    }
+}
+
+pub fn check_crashed_never_vote(states: &[std::sync::Arc<ConsensusState>], crashed: &[Id]) -> bool {
+    // Nodes are crashed from the start, so they never had a chance to vote.
+    // Any vote attributed to one was fabricated somewhere along the way.
+    states
+        .iter()
+        .all(|s| !s.votes_received.values().flatten().any(|voter| crashed.contains(voter)))
+}
+
+pub fn has_decision<S: Decision>(states: &[std::sync::Arc<S>]) -> bool {
+    // Check if at least one node has decided
+    states.iter().any(|s| s.decided_value().is_some())
+}
+
+/// The value at least `quorum_size` nodes decided, if any: consensus proper,
+/// where [`has_decision`] is happy with a single node. Counts heads, not
+/// stake.
+pub fn consensus_reached(states: &[std::sync::Arc<ConsensusState>], quorum_size: usize) -> Option<Value> {
+    decision_histogram(states)
+        .into_iter()
+        .find(|&(_, deciders)| deciders > 0 && deciders >= quorum_size)
+        .map(|(value, _)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stateright::actor::{ActorModel, Network};
+    use stateright::{Checker, Expectation, Model};
+
+    /// Checks Agreement and Validity for an `node_count`-node cluster on a
+    /// reliable network, with node 0 bootstrapping V0 against a rival V1 at
+    /// node 1. Exploration stops at a depth that leaves room for both bids
+    /// to play out, so larger clusters still finish quickly.
+    pub fn assert_agreement_for(node_count: usize) {
+        let peer_ids: Vec<Id> = (0..node_count).map(Id::from).collect();
+        let actors = (0..node_count).map(|i| {
+            let actor = ConsensusActor::new(peer_ids.clone());
+            if i == 1 { actor.with_proposal(Value::V1) } else { actor }
+        });
+        let model = ActorModel::new((), ())
+            .actors(actors)
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "agreement", |_, state| check_agreement(&state.actor_states))
+            .property(Expectation::Always, "validity", |_, state| {
+                check_validity(&state.actor_states, &[Value::V0, Value::V1])
+            })
+            .property(Expectation::Sometimes, "progress", |_, state| has_decision(&state.actor_states));
+
+        let result = model.checker().target_max_depth(6 * node_count).spawn_bfs().join();
+        assert!(result.discovery("agreement").is_none(), "Agreement violated with {} nodes", node_count);
+        assert!(result.discovery("validity").is_none(), "Validity violated with {} nodes", node_count);
+        assert!(result.discovery("progress").is_some(), "no decision within the depth bound");
+        assert!(result.unique_state_count() > 0, "Should explore at least some states");
+    }
+
+    #[test]
+    fn test_three_node_consensus() {
+        assert_agreement_for(3);
+    }
+
+    #[test]
+    fn test_four_node_consensus() {
+        assert_agreement_for(4);
+    }
+
+    #[test]
+    fn test_five_node_consensus() {
+        assert_agreement_for(5);
+    }
+
+    #[test]
+    fn test_broadcast_sends_in_id_order() {
+        let peer_ids: Vec<Id> = [2, 0, 3, 1].into_iter().map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids);
+        let mut effects = Effects::default();
+        actor.broadcast_ordered(Id::from(2), ConsensusMsg::StateRequest, &mut effects);
+        let sent: Vec<Id> = effects.sends.into_iter().map(|(dst, _)| dst).collect();
+        assert_eq!(sent, [0, 1, 3].map(Id::from));
+    }
+
+    #[test]
+    fn test_invalid_proposal_gets_no_vote() {
+        // Node 0 pushes V2, which node 2 won't accept, against node 1's V0.
+        // Both candidates only vote for themselves, so node 2 picks the winner.
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let validator = ConsensusActor::new(peer_ids.clone())
+            .with_leader(None)
+            .with_valid_values([Value::V0, Value::V1]);
+
+        let mut state = Cow::Owned(validator.on_start(Id::from(2), &mut Out::new()));
+        let mut out = Out::new();
+        let propose = ConsensusMsg::Propose { value: Value::V2, term: 0, slot: 0 };
+        validator.on_msg(Id::from(2), &mut state, Id::from(0), propose, &mut out);
+        assert!(out.is_empty() && state.proposed_value.is_none(), "voted for V2");
+
+        let model = ActorModel::new((), ())
+            .actor(ConsensusActor::new(peer_ids.clone()).with_proposal(Value::V2))
+            .actor(ConsensusActor::new(peer_ids.clone()).with_proposal(Value::V0))
+            .actor(validator)
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "never V2", |_, state| {
+                state.actor_states.iter().all(|s| s.decided_value != Some(Value::V2))
+            })
+            .property(Expectation::Sometimes, "V0 decided", |_, state| {
+                state.actor_states.iter().all(|s| s.decided_value == Some(Value::V0))
+            });
+
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("never V2").is_none(), "V2 was decided");
+        assert!(result.discovery("V0 decided").is_some(), "consensus should go ahead on V0");
+    }
+
+    #[test]
+    fn test_learners_agree_with_voters() {
+        // Voters 0-2 decide with a quorum of 2; learners 3 and 4 just listen
+        let peer_ids: Vec<Id> = (0..5).map(Id::from).collect();
+        let learners = [Id::from(3), Id::from(4)];
+        let voter = ConsensusActor::new(peer_ids.clone()).with_learners(learners);
+        assert_eq!(voter.quorum_size(), 2, "learners hold no stake");
+
+        let model = ActorModel::new((), ())
+            .actor(voter.clone().with_proposal(Value::V1))
+            .actor(voter.clone())
+            .actor(voter)
+            .actors(learners.map(|_| ConsensusActor::learner(peer_ids.clone()).with_learners(learners)))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "agreement", |_, state| check_agreement(&state.actor_states))
+            .property(Expectation::Always, "learners stay passive", |_, state| {
+                state.actor_states[3..].iter().all(|s| s.role == NodeRole::Learner)
+                    && state.actor_states.iter().all(|s| {
+                        s.votes_received.values().flatten().all(|voter| usize::from(*voter) < 3)
+                    })
+            })
+            .property(Expectation::Sometimes, "all five decided", |_, state| {
+                state.actor_states.iter().all(|s| s.decided_value == Some(Value::V1))
+            });
+
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("agreement").is_none(), "Agreement violated");
+        assert!(result.discovery("learners stay passive").is_none());
+        assert!(result.discovery("all five decided").is_some(), "learners should learn the decision");
+    }
+
+    #[test]
+    fn test_tie_break_resolves_three_way_split() {
+        let split = |tie_break: bool| {
+            let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+            ActorModel::new((), ())
+                .actors(Value::ALL.map(|value| {
+                    ConsensusActor::new(peer_ids.clone()).with_proposal(value).with_tie_break(tie_break)
+                }))
+                .init_network(Network::new_unordered_nonduplicating([]))
+                .property(Expectation::Always, "Agreement", |_, state| check_agreement(&state.actor_states))
+                .property(Expectation::Sometimes, "Progress", |_, state| has_decision(&state.actor_states))
+                .property(Expectation::Eventually, "all decided", |_, state| all_decided(&state.actor_states))
+                .property(Expectation::Sometimes, "all on V2", |_, state| {
+                    state.actor_states.iter().all(|s| s.decided_value == Some(Value::V2))
+                })
+                .checker()
+                .spawn_bfs()
+                .join()
+        };
+
+        let stuck = split(false);
+        assert!(stuck.discovery("Progress").is_none(), "1-1-1 deadlocks without a tie-break");
+
+        let result = split(true);
+        assert!(result.discovery("Agreement").is_none(), "Agreement violated");
+        assert!(result.discovery("Progress").is_some());
+        assert!(result.discovery("all decided").is_none(), "every run should converge");
+        assert!(result.discovery("all on V2").is_some(), "the largest bid can win outright");
+    }
+
+    #[test]
+    fn test_all_propose_keeps_agreement() {
+        let config = SimConfig { all_propose: true, ..SimConfig::default() };
+        let proposals: Vec<_> = config.actors().iter().map(|a| a.proposal).collect();
+        assert_eq!(proposals, Value::ALL.map(Some));
+        assert_eq!(config.contention_scenarios(), vec![config.clone()]);
+
+        // Three Candidates, each voting only for itself, never reach 2 of 3,
+        // but none of them may decide something the others can't agree with
+        let summary = config.run_check();
+        assert!(summary.violations.is_empty(), "violated {:?}", summary.violations);
+        assert!(!summary.progress, "a 1-1-1 split has no quorum");
+
+        let four = SimConfig { nodes: 4, all_propose: true, ..SimConfig::default() };
+        assert!(four.validate().is_err(), "4 nodes can't each have a distinct value out of 3");
+    }
+
+    #[test]
+    fn test_lowest_id_bootstraps_a_decision() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        assert_eq!(ConsensusActor::new(peer_ids.clone()).leader_id, Some(Id::from(0)));
+
+        let model = ActorModel::new((), ())
+            .actors((0..3).map(|_| ConsensusActor::new(peer_ids.clone())))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Eventually, "decides", |_, state| {
+                has_decision(&state.actor_states)
+            });
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("decides").is_none(), "some run never decides");
+        assert!(result.unique_state_count() > 1, "bootstrap leader should start the protocol");
+    }
+
+    #[test]
+    fn test_protocol_over_u8_values() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = |proposal: Option<u8>| {
+            let actor = ConsensusActor::<u8>::with_peers(peer_ids.clone()).with_leader(None);
+            match proposal {
+                Some(value) => actor.with_proposal(value),
+                None => actor,
+            }
+        };
+        let model = ActorModel::new((), ())
+            .actors([actor(Some(7)), actor(Some(200)), actor(None)])
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "Agreement", |_, state| {
+                check_agreement(&state.actor_states)
+            })
+            .property(Expectation::Always, "Validity", |_, state| {
+                check_validity(&state.actor_states, &[7, 200])
+            })
+            .property(Expectation::Sometimes, "Progress", |_, state| {
+                has_decision(&state.actor_states)
+            });
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none());
+        assert!(result.discovery("Validity").is_none());
+        let path = result.discovery("Progress").expect("a u8 cluster should decide too");
+        let decided: Option<u8> = path.last_state().actor_states.iter().find_map(|s| s.decided_value);
+        assert!(matches!(decided, Some(7 | 200)));
+    }
+
+    #[test]
+    fn test_survivors_decide_when_bootstrap_leader_crashed() {
+        // n = 3 with the would-be leader (node 0) crashed; the remaining
+        // majority has to get going on its own. Only node 1 runs a timer - two
+        // simultaneous one-shot timeouts would split the vote 1-1 for good.
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let model = ActorModel::new((), ())
+            .actor(ConsensusActor::new(peer_ids.clone()).with_crashed(true).with_election_timeout(true))
+            .actor(ConsensusActor::new(peer_ids.clone()).with_election_timeout(true))
+            .actor(ConsensusActor::new(peer_ids.clone()))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "Agreement", |_, state| {
+                check_agreement(&state.actor_states)
+            })
+            .property(Expectation::Eventually, "survivors decide", |_, state| {
+                all_decided(&state.actor_states[1..])
+            });
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none());
+        assert!(result.discovery("survivors decide").is_none(), "nodes 1 and 2 should always decide");
+    }
+
+    #[test]
+    fn test_two_slots_decide_consistently() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = |proposal| {
+            ConsensusActor::new(peer_ids.clone())
+                .with_leader(None)
+                .with_proposal(proposal)
+                .with_slots(2)
+        };
+        // Two proposers race for each slot, so the slots may go either way
+        let model = ActorModel::new(SimConfig::default().model_config(), ())
+            .actors([actor(Value::V0), actor(Value::V1)])
+            .actor(ConsensusActor::new(peer_ids.clone()).with_leader(None).with_slots(2))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "Agreement", |model, state| {
+                SafetyProperty::Agreement.eval(&model.cfg, &state.actor_states)
+            })
+            .property(Expectation::Sometimes, "log filled", |_, state| {
+                state.actor_states.iter().all(|s| s.decided_log.len() == 2)
+            });
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none(), "slots disagree across nodes");
+
+        let path = result.discovery("log filled").expect("both slots should get decided");
+        let logs: Vec<_> = path.last_state().actor_states.iter().map(|s| s.decided_log.clone()).collect();
+        assert!(logs.iter().all(|log| *log == logs[0]), "{:?}", logs);
+        assert!(path.last_state().actor_states.iter().all(|s| s.slot == 1 && s.decided_value.is_some()));
+
+        // Same slot, different values is caught even if the current decisions agree
+        let split = |log: Vec<Value>| std::sync::Arc::new(ConsensusState { decided_log: log, ..ConsensusState::default() });
+        assert!(!check_log_agreement(&[split(vec![Value::V0, Value::V1]), split(vec![Value::V1])]));
+        assert!(check_log_agreement(&[split(vec![Value::V0, Value::V1]), split(vec![Value::V0])]));
+    }
+
+    #[test]
+    fn test_node_one_slot_behind_catches_up() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let follower = ConsensusActor::new(peer_ids.clone()).with_leader(None).with_slots(2);
+        let commit = |slot| ConsensusMsg::Commit {
+            value: Value::V0,
+            term: 0,
+            slot,
+            certificate: [Id::from(0), Id::from(1)].into(),
+        };
+
+        // Slot 1's round overtakes slot 0's Commit; node 2 holds on to it
+        let early = ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 1 };
+        let (state, effects) = follower.step(Id::from(2), &ConsensusState::default(), Id::from(0), &early);
+        assert_eq!((state.slot, state.deferred.len()), (0, 1));
+        assert!(effects.is_empty());
+
+        // Deciding slot 0 moves it on, and the held Propose gets its vote
+        let (state, effects) = follower.step(Id::from(2), &state, Id::from(0), &commit(0));
+        assert_eq!((state.slot, state.decided_log.clone()), (1, vec![Value::V0]));
+        assert!(state.deferred.is_empty());
+        assert_eq!(effects.sends, vec![(Id::from(0), ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 1 })]);
+
+        let (state, _) = follower.step(Id::from(2), &state, Id::from(0), &commit(1));
+        assert_eq!((state.decided_value, state.decided_log), (Some(Value::V0), vec![Value::V0, Value::V0]));
+
+        // However the slots' messages interleave, every node gets through both
+        let model = ActorModel::new((), ())
+            .actor(ConsensusActor::new(peer_ids.clone()).with_proposal(Value::V0).with_slots(2))
+            .actors((1..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_leader(None).with_slots(2)))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Eventually, "log filled", |_, state| {
+                state.actor_states.iter().all(|s| s.decided_log.len() == 2)
+            });
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("log filled").is_none(), "a node stalled short of the last slot");
+    }
+
+    #[test]
+    fn test_decided_node_helps_laggard_catch_up() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let cluster = |catch_up| {
+            ActorModel::new((), ())
+                .actor(ConsensusActor::new(peer_ids.clone()).with_proposal(Value::V0).with_catch_up(catch_up))
+                .actors((1..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_leader(None).with_catch_up(catch_up)))
+                .init_network(Network::new_unordered_nonduplicating([]))
+                .lossy_network(LossyNetwork::Yes)
+                .property(Expectation::Always, "Agreement", |_, state| {
+                    check_agreement(&state.actor_states)
+                })
+        };
+        let result = cluster(true).checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none(), "catch-up Commits broke Agreement");
+
+        // Node 2's Commit is lost, but its late vote reaches the decided
+        // leader, which tells it the outcome
+        let deliver = |src: usize, dst: usize, msg| ActorModelAction::Deliver {
+            src: Id::from(src),
+            dst: Id::from(dst),
+            msg,
+        };
+        let propose = ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 };
+        let vote = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+        let commit = ConsensusMsg::Commit {
+            value: Value::V0,
+            term: 0,
+            slot: 0,
+            certificate: [Id::from(0), Id::from(1)].into(),
+        };
+        let actions = [
+            deliver(0, 1, propose.clone()),
+            deliver(1, 0, vote.clone()),
+            ActorModelAction::Drop(Envelope { src: Id::from(0), dst: Id::from(2), msg: commit.clone() }),
+            deliver(0, 2, propose),
+            deliver(2, 0, vote),
+            deliver(0, 2, commit),
+        ];
+        let model = cluster(true);
+        let path = Path::from_actions(&model, model.init_states().remove(0), &actions).expect("node 0 re-sends its Commit");
+        assert_eq!(path.last_state().actor_states[2].decided_value, Some(Value::V0));
+
+        let model = cluster(false);
+        assert!(
+            Path::from_actions(&model, model.init_states().remove(0), &actions).is_none(),
+            "without catch-up the lost Commit never comes back"
+        );
+    }
+
+    #[test]
+    fn test_commit_retry_recovers_lost_commit() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let model = ActorModel::new((), ())
+            .actor(ConsensusActor::new(peer_ids.clone()).with_proposal(Value::V0).with_commit_retry(true))
+            .actors((1..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_leader(None).with_commit_retry(true)))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .lossy_network(LossyNetwork::Yes)
+            .property(Expectation::Always, "Agreement", |_, state| {
+                check_agreement(&state.actor_states)
+            });
+        // Retries keep adding copies, so only a bounded prefix is checkable
+        let result = model.clone().checker().target_max_depth(8).spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none(), "re-sent Commits broke Agreement");
+
+        // Node 2's Commit is lost; the retry still gets it there
+        let deliver = |src: usize, dst: usize, msg| ActorModelAction::Deliver {
+            src: Id::from(src),
+            dst: Id::from(dst),
+            msg,
+        };
+        let propose = ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 };
+        let vote = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+        let commit = ConsensusMsg::Commit {
+            value: Value::V0,
+            term: 0,
+            slot: 0,
+            certificate: [Id::from(0), Id::from(1)].into(),
+        };
+        let path = Path::from_actions(
+            &model,
+            model.init_states().remove(0),
+            &[
+                deliver(0, 1, propose),
+                deliver(1, 0, vote),
+                ActorModelAction::Drop(Envelope { src: Id::from(0), dst: Id::from(2), msg: commit.clone() }),
+                ActorModelAction::Timeout(Id::from(0), Timer::CommitRetry),
+                deliver(0, 2, commit.clone()),
+                deliver(0, 1, commit),
+                // A quorum has it, but the leader keeps going until node 2 acks too
+                deliver(1, 0, ConsensusMsg::CommitAck { term: 0, slot: 0 }),
+            ],
+        )
+        .expect("the retry re-sends the dropped Commit");
+        let last = path.last_state();
+        assert!(all_decided(&last.actor_states));
+        assert_eq!(last.actor_states[0].commit_acks, [Id::from(0), Id::from(1)].into());
+        assert!(last.actor_states[0].pending_commit.is_some());
+    }
+
+    #[test]
+    fn test_commit_retry_reaches_every_node() {
+        // Stateright only judges Eventually on runs that stop, so a run that
+        // drops every retry forever never counts against it: the network is
+        // lossy but fair. Whatever runs do stop have to end with everyone
+        // decided, unless the leader never got as far as committing.
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let model = ActorModel::new((), ())
+            .actor(ConsensusActor::new(peer_ids.clone()).with_proposal(Value::V0).with_commit_retry(true))
+            .actors((1..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_leader(None).with_commit_retry(true)))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .lossy_network(LossyNetwork::Yes)
+            .property(Expectation::Eventually, "CommitReachesEveryone", |_, state| {
+                let stalled = state.actor_states[0].decided_value.is_none() && state.network.len() == 0;
+                all_decided(&state.actor_states) || stalled
+            });
+        // Retries keep adding copies, so bound the depth; the shortest way to
+        // strand node 2 (lose its Commit once node 1 has acked) is 6 steps
+        let result = model.checker().target_max_depth(10).spawn_bfs().join();
+        assert!(result.discovery("CommitReachesEveryone").is_none(), "a node never got the Commit");
+    }
+
+    #[test]
+    fn test_gossip_recovers_a_lost_commit_sooner_than_retry() {
+        // A lossy network: once one of the leader's Commits is lost, compare
+        // how quickly each mechanism still gets every node to decide. The
+        // history counts the leader's Commits (sent, delivered), so whatever
+        // is neither delivered nor in flight was dropped.
+        fn is_leader_commit(env: &Envelope<&ConsensusMsg>) -> bool {
+            usize::from(env.src) == 0 && matches!(env.msg, ConsensusMsg::Commit { .. })
+        }
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let cluster = |configure: fn(ConsensusActor) -> ConsensusActor| {
+            ActorModel::new((), (0u8, 0u8))
+                .actor(configure(ConsensusActor::new(peer_ids.clone())).with_proposal(Value::V0))
+                .actors((1..3).map(|_| configure(ConsensusActor::new(peer_ids.clone()).with_leader(None))))
+                .init_network(Network::new_unordered_nonduplicating([]))
+                .lossy_network(LossyNetwork::Yes)
+                .record_msg_out(|_, &(sent, delivered), env| {
+                    is_leader_commit(&env).then_some((sent + 1, delivered))
+                })
+                .record_msg_in(|_, &(sent, delivered), env| {
+                    is_leader_commit(&env).then_some((sent, delivered + 1))
+                })
+                .property(Expectation::Always, "Agreement", |_, state| {
+                    check_agreement(&state.actor_states)
+                })
+                .property(Expectation::Sometimes, "AllDecidedDespiteLoss", |_, state| {
+                    let (sent, delivered) = state.history;
+                    let in_flight = state.network.iter_all().filter(is_leader_commit).count();
+                    all_decided(&state.actor_states) && usize::from(sent) > usize::from(delivered) + in_flight
+                })
+        };
+        // Retries repeat forever, so bound both searches the same way
+        let shortest = |configure: fn(ConsensusActor) -> ConsensusActor| {
+            let result = cluster(configure).checker().target_max_depth(8).spawn_bfs().join();
+            assert!(result.discovery("Agreement").is_none(), "recovering a lost Commit broke Agreement");
+            result.discovery("AllDecidedDespiteLoss").expect("every node still decides").into_actions()
+        };
+
+        let gossip = shortest(|actor| actor.with_gossip(true));
+        let retry = shortest(|actor| actor.with_commit_retry(true));
+        // The follower that got the Commit passes it straight on, where the
+        // leader has to wait for its retry timer before resending
+        assert!(gossip.iter().any(|action| {
+            matches!(action, ActorModelAction::Deliver { src, dst, msg: ConsensusMsg::Commit { .. } }
+                if usize::from(*src) != 0 && usize::from(*dst) != 0)
+        }));
+        assert!(!gossip.iter().any(|action| matches!(action, ActorModelAction::Timeout(..))));
+        assert!(retry.iter().any(|action| matches!(action, ActorModelAction::Timeout(..))));
+        assert!(gossip.len() < retry.len(), "gossip took {} steps, retry {}", gossip.len(), retry.len());
+    }
+
+    #[test]
+    fn test_leader_counts_commit_acks() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let model = ActorModel::new((), ())
+            .actor(ConsensusActor::new(peer_ids.clone()).with_proposal(Value::V0).with_commit_acks(true))
+            .actors((1..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_leader(None).with_commit_acks(true)))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "Agreement", |_, state| {
+                check_agreement(&state.actor_states)
+            })
+            .property(Expectation::Always, "NoRetries", |_, state| {
+                state.actor_states.iter().all(|s| s.pending_commit.is_none())
+            })
+            .property(Expectation::Sometimes, "CommittedQuorum", |_, state| {
+                state.actor_states[0].is_committed_quorum(2)
+            })
+            .property(Expectation::Sometimes, "AllAcked", |_, state| {
+                state.actor_states[0].is_committed_quorum(3)
+            });
+        let result = model.checker().spawn_bfs().join();
+
+        assert!(result.discovery("Agreement").is_none(), "acks broke Agreement");
+        assert!(result.discovery("NoRetries").is_none(), "acks alone shouldn't arm retries");
+        let path = result.discovery("CommittedQuorum").expect("the leader should see a quorum of acks");
+        let last = path.last_state();
+        // The leader counts itself as soon as it sends the Commit; everyone
+        // else only acks once they've decided
+        let acked = last.actor_states[0].commit_acks.iter().map(|&id| usize::from(id));
+        assert!(acked.filter(|&i| i != 0).all(|i| last.actor_states[i].decided_value == Some(Value::V0)));
+        assert!(result.discovery("AllAcked").is_some(), "every node's ack should be countable");
+    }
+
+    #[test]
+    fn test_client_request_starts_consensus() {
+        // Nobody bootstraps anything; node 0 only campaigns once the client's
+        // request for V1 reaches it
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let mut model = SimConfig::default().build_model().property(Expectation::Sometimes, "AllDecided", |_, state| {
+            all_decided(&state.actor_states)
+        });
+        model.actors = (0..3)
+            .map(|i| {
+                let actor = ConsensusActor::new(peer_ids.clone()).with_leader(None);
+                if i == 0 { actor.with_client_request(Value::V1) } else { actor }
+            })
+            .collect();
+        assert_eq!(proposed_values(&model), vec![Value::V1], "the request is the only proposal in play");
+
+        let init = &model.init_states()[0];
+        assert!(init.actor_states.iter().all(|s| s.role == NodeRole::Follower));
+        let request = Envelope { src: Id::from(0), dst: Id::from(0), msg: ConsensusMsg::ClientRequest { value: Value::V1 } };
+        assert_eq!(init.network.iter_deliverable().map(|e| e.to_cloned_msg()).collect::<Vec<_>>(), vec![request]);
+
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("Validity").is_none(), "decided something nobody asked for");
+        for property in SafetyProperty::ALL {
+            assert!(result.discovery(property.name()).is_none(), "{} violated", property.name());
+        }
+        assert!(result.discovery("AllDecided").is_some(), "the request should get V1 decided");
+    }
+
+    #[test]
+    fn test_one_candidacy_per_term() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids).with_leader(None).with_election_timeout(true);
+        let proposes = |out: &Out<ConsensusActor>| {
+            out.iter().filter(|c| matches!(c, Command::Send(_, ConsensusMsg::Propose { .. }))).count()
+        };
+
+        // The election timer fires and we run in term 1...
+        let mut state = Cow::Owned(actor.on_start(Id::from(0), &mut Out::new()));
+        let mut out = Out::new();
+        actor.on_timeout(Id::from(0), &mut state, &Timer::Election, &mut out);
+        assert_eq!((state.role, state.term, proposes(&out)), (NodeRole::Candidate, 1, 2));
+        assert!(state.has_proposed_in_term);
+
+        // ...and then a client asks too: same term, so no second round of Proposes
+        let (after, effects) = actor.step(Id::from(0), &state, Id::from(0), &ConsensusMsg::ClientRequest { value: Value::V1 });
+        assert_eq!((&after, after.rejected), (&*state, 1));
+        assert!(effects.is_empty());
+
+        // Even a node that somehow fell back to Follower within the term stays out
+        let withdrawn = ConsensusState { role: NodeRole::Follower, proposed_value: None, ..state.into_owned() };
+        let (after, effects) = actor.step(Id::from(0), &withdrawn, Id::from(0), &ConsensusMsg::ClientRequest { value: Value::V1 });
+        assert_eq!((after.role, effects.sends.len()), (NodeRole::Follower, 0));
+
+        // A newer term lifts the guard
+        let propose = ConsensusMsg::Propose { value: Value::V2, term: 2, slot: 0 };
+        let (after, _) = actor.step(Id::from(0), &withdrawn, Id::from(1), &propose);
+        assert!(!after.has_proposed_in_term);
+    }
+
+    #[test]
+    fn test_three_way_split_aborts() {
+        // Every node campaigns for its own value and nobody breaks the tie,
+        // so no value can get past one vote
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let split = |abort_after| {
+            ActorModel::new((), ())
+                .actors(Value::ALL.into_iter().map(|v| {
+                    ConsensusActor::new(peer_ids.clone()).with_proposal(v).with_abort_after(abort_after)
+                }))
+                .init_network(Network::new_unordered_nonduplicating([]))
+                .property(Expectation::Always, "NoSpuriousAbort", |_, state| {
+                    check_no_spurious_abort(&state.actor_states, 2)
+                })
+                .property(Expectation::Sometimes, "AllAborted", |_, state| {
+                    state.actor_states.iter().all(|s| s.role == NodeRole::Aborted)
+                })
+        };
+        let result = split(2).checker().spawn_bfs().join();
+        assert!(result.discovery("NoSpuriousAbort").is_none(), "aborted with a quorum in reach");
+        let path = result.discovery("AllAborted").expect("every node should give up on the split");
+        assert!(path.last_state().actor_states.iter().all(|s| s.stalls == 2 && s.rivals.len() == 2));
+
+        // A single proposer always has its quorum in reach, so never aborts
+        let model = ActorModel::new((), ())
+            .actor(ConsensusActor::new(peer_ids.clone()).with_proposal(Value::V0).with_abort_after(1))
+            .actors((1..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_leader(None).with_abort_after(1)))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "NeverAborts", |_, state| {
+                state.actor_states.iter().all(|s| s.role != NodeRole::Aborted)
+            });
+        assert!(model.checker().spawn_bfs().join().discovery("NeverAborts").is_none());
+    }
+
+    #[test]
+    fn test_two_phase_survives_takeover_that_breaks_one_phase() {
+        // Node 0 decides V1 in term 0. Node 2 never heard its Propose, times
+        // out and campaigns for V0 in term 1; node 1, which voted for V1,
+        // votes again. With one phase that's a second quorum and a second
+        // decision. With two, node 1 promised V1 before node 0 committed, so
+        // it refuses to back V0.
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let model = |two_phase| {
+            let actor = |election_timeout| {
+                ConsensusActor::new(peer_ids.clone())
+                    .with_leader(None)
+                    .with_election_timeout(election_timeout)
+                    .with_two_phase(two_phase)
+            };
+            ActorModel::new((), ())
+                .actors([actor(false).with_proposal(Value::V1), actor(false), actor(true)])
+                .init_network(Network::new_unordered_nonduplicating([]))
+                .property(Expectation::Always, "Agreement", |_, state| {
+                    check_agreement(&state.actor_states)
+                })
+                .property(Expectation::Sometimes, "decides", |_, state| {
+                    has_decision(&state.actor_states)
+                })
+        };
+
+        let one_phase = model(false).checker().spawn_bfs().join();
+        assert!(one_phase.discovery("Agreement").is_some(), "the takeover should split one phase");
+
+        let two_phase = model(true).checker().spawn_bfs().join();
+        assert!(two_phase.discovery("Agreement").is_none(), "locking should prevent the split");
+        let path = two_phase.discovery("decides").expect("two phases can still decide");
+        assert!(path
+            .into_actions()
+            .iter()
+            .any(|a| matches!(a, ActorModelAction::Deliver { msg: ConsensusMsg::Promise { .. }, .. })));
+    }
+
+    #[test]
+    fn test_election_timeout_replaces_silent_leader() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        // Node 0 never proposes; the others only get going through their timers
+        let model = ActorModel::new((), ())
+            .actor(ConsensusActor::new(peer_ids.clone()).with_leader(None))
+            .actors((1..3).map(|_| {
+                ConsensusActor::new(peer_ids.clone())
+                    .with_leader(None)
+                    .with_election_timeout(true)
+            }))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "Agreement", |_, state| {
+                check_agreement(&state.actor_states)
+            })
+            .property(Expectation::Eventually, "decides", |_, state| {
+                has_decision(&state.actor_states)
+            });
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none(), "racing takeovers must still agree");
+        assert!(result.discovery("decides").is_none(), "a timed-out follower should take over");
+    }
+
+    #[test]
+    fn test_consensus_state_equality() {
+        let state1 = ConsensusState {
+            role: NodeRole::Follower,
+            proposed_value: Some(Value::V0),
+            votes_received: HashMap::new(),
+            decided_value: None,
+            ..ConsensusState::default()
+        };
+
+        let mut state2 = ConsensusState {
+            role: NodeRole::Follower,
+            proposed_value: Some(Value::V0),
+            votes_received: HashMap::new(),
+            decided_value: None,
+            ..ConsensusState::default()
+        };
+
+        assert_eq!(state1, state2, "{:?}", diff_states(&state1, &state2));
+
+        state2.role = NodeRole::Candidate;
+        assert_ne!(state1, state2);
+    }
+
+    #[test]
+    fn test_diff_states_lists_changed_fields() {
+        let before = ConsensusState {
+            proposed_value: Some(Value::V0),
+            ..ConsensusState::default()
+        };
+        let after = ConsensusState {
+            role: NodeRole::Candidate,
+            votes_received: HashMap::from([(Value::V0, [Id::from(1)].into_iter().collect())]),
+            ..before.clone()
+        };
+        assert_eq!(
+            diff_states(&before, &after),
+            vec!["role: Follower -> Candidate".to_string(), "votes: +{1}".to_string()]
+        );
+        assert!(diff_states(&after, &after).is_empty());
+
+        let trace = render_trace(&SimConfig::default(), &split_commit_trace());
+        assert!(trace.contains("    node 1 decided: None -> Some(V0)"), "{}", trace);
+    }
+
+    #[test]
+    fn test_five_node_model_uses_majority_quorum() {
+        let model = SimConfig {
+            nodes: 5,
+            ..SimConfig::default()
+        }
+        .build_model();
+        assert_eq!(model.actors.len(), 5);
+        assert!(model.actors.iter().all(|a| a.quorum_size() == 3));
+    }
+
+    #[test]
+    fn test_quorum_calculation() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids);
+        
+        assert_eq!(actor.quorum_size(), 2, "Quorum for 3 nodes should be 2");
+
+        let mut votes = HashSet::new();
+        assert!(!actor.has_quorum(&votes), "Empty votes shouldn't be quorum");
+
+        votes.insert(Id::from(0));
+        assert!(!actor.has_quorum(&votes), "1 vote isn't quorum for 3 nodes");
+
+        votes.insert(Id::from(1));
+        assert!(actor.has_quorum(&votes), "2 votes should be quorum for 3 nodes");
+    }
+
+    #[test]
+    fn test_quorum_policies() {
+        // n = 4 and n = 7 are 3f + 1 for f = 1 and f = 2
+        for (n, majority, two_thirds) in [(4, 3, 3), (7, 4, 5)] {
+            assert!(!SimpleMajority.is_quorum(majority - 1, n));
+            assert!(SimpleMajority.is_quorum(majority, n));
+            assert!(!ByzantineTwoThirds.is_quorum(two_thirds - 1, n));
+            assert!(ByzantineTwoThirds.is_quorum(two_thirds, n), "2f + 1 of {} nodes", n);
+
+            let peer_ids: Vec<Id> = (0..n).map(Id::from).collect();
+            let actor = ConsensusActor::new(peer_ids);
+            assert_eq!(actor.quorum_size(), majority, "new defaults to a simple majority");
+            assert_eq!(actor.with_quorum_policy(ByzantineTwoThirds).quorum_size(), two_thirds);
+        }
+    }
+
+    #[test]
+    fn test_quorum_intersection_validation() {
+        for n in 1..=12 {
+            assert_eq!(SimpleMajority.validate(n), Ok(()), "n = {}", n);
+            assert_eq!(ByzantineTwoThirds.validate(n), Ok(()), "n = {}", n);
+            let peer_ids: Vec<Id> = (0..n).map(Id::from).collect();
+            assert_eq!(ConsensusActor::new(peer_ids).validate_quorums(), Ok(()), "n = {}", n);
+        }
+        assert_eq!(
+            Threshold(2).validate(4),
+            Err("quorum 2 does not intersect: two quorums of 4 nodes can be disjoint".to_string())
+        );
+
+        // Three votes' worth of stake is a quorum of 7, and nodes 0 and 1
+        // each carry that much on their own
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let heavy = ConsensusActor::new(peer_ids).with_weights(vec![3, 3, 1]);
+        assert_eq!(heavy.clone().validate_quorums(), Ok(()), "a stake majority always intersects");
+        assert_eq!(
+            heavy.with_quorum_policy(Threshold(3)).validate_quorums(),
+            Err("{0} and {1, 2} are disjoint quorums".to_string())
+        );
+
+        // Too many peers to try every split, but the check doesn't need to
+        let peer_ids: Vec<Id> = (0..40).map(Id::from).collect();
+        let big = ConsensusActor::new(peer_ids.clone()).with_weights((1..=40).collect());
+        assert_eq!(big.validate_quorums(), Ok(()));
+        assert_eq!(
+            big.with_quorum_policy(Threshold(410)).validate_quorums(),
+            Err("quorum 410 is at most half the cluster's total stake 820, so two quorums can be disjoint".to_string())
+        );
+    }
+
+    #[test]
+    fn test_quorum_follows_membership() {
+        let mut actor = ConsensusActor::new((0..3).map(Id::from).collect());
+        assert_eq!(actor.quorum_size(), 2);
+        actor.add_peer(Id::from(3));
+        assert_eq!(actor.quorum_size(), 3);
+        actor.remove_peer(Id::from(3));
+        assert_eq!(actor.quorum_size(), 2);
+
+        // The new peer has unit stake
+        actor.add_peer(Id::from(3));
+        assert!(actor.has_quorum(&[Id::from(0), Id::from(1), Id::from(3)]));
+        assert!(!actor.has_quorum(&[Id::from(0), Id::from(3)]));
+    }
+
+    #[test]
+    fn test_remove_peer_lowers_quorum() {
+        let mut actor = ConsensusActor::new((0..5).map(Id::from).collect());
+        assert_eq!(actor.quorum_size(), 3);
+        actor.remove_peer(Id::from(4));
+        actor.remove_peer(Id::from(3));
+        assert_eq!((actor.peers(), actor.quorum_size()), (&[0, 1, 2].map(Id::from)[..], 2));
+        actor.remove_peer(Id::from(7));
+        assert_eq!(actor.quorum_size(), 2, "not a member, nothing to remove");
+        actor.add_peer(Id::from(2));
+        assert_eq!((actor.peers().len(), actor.quorum_size()), (3, 2), "a member already, nothing to add");
+
+        // The removed peer's stake goes with it: 6 of 7 needs 4, 3 of 4 needs 3
+        let mut weighted = ConsensusActor::new((0..3).map(Id::from).collect()).with_weights(vec![3, 2, 2]);
+        assert_eq!(weighted.quorum_size(), 4);
+        weighted.remove_peer(Id::from(0));
+        assert_eq!(weighted.quorum_size(), 3);
+        assert!(!weighted.has_quorum(&[Id::from(1)]), "2 of 4 isn't a majority");
+    }
+
+    #[test]
+    fn test_byzantine_tolerance_sets_2f_plus_1() {
+        for (f, n, quorum) in [(1, 4, 3), (2, 7, 5)] {
+            let peer_ids: Vec<Id> = (0..n).map(Id::from).collect();
+            let actor = ConsensusActor::with_byzantine_tolerance(peer_ids, f);
+            assert_eq!(actor.quorum_size(), quorum, "f = {}", f);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3f + 1 = 4 nodes, got 3")]
+    fn test_byzantine_tolerance_rejects_small_cluster() {
+        ConsensusActor::with_byzantine_tolerance((0..3).map(Id::from).collect(), 1);
+    }
+
+    #[test]
+    fn test_agreement_property() {
+        // Test agreement checker with same decisions
+        let states: Vec<std::sync::Arc<ConsensusState>> = vec![
             std::sync::Arc::new(ConsensusState {
                 role: NodeRole::Decided,
                 proposed_value: Some(Value::V0),
-                votes_received: HashSet::new(),
+                votes_received: HashMap::new(),
                 decided_value: Some(Value::V0),
+                ..ConsensusState::default()
             }),
             std::sync::Arc::new(ConsensusState {
                 role: NodeRole::Decided,
                 proposed_value: Some(Value::V0),
-                votes_received: HashSet::new(),
+                votes_received: HashMap::new(),
                 decided_value: Some(Value::V0),
+                ..ConsensusState::default()
             }),
         ];
         assert!(check_agreement(&states), "Same values should pass agreement");
 
-        // Test with different decisions (should fail)
-        let bad_states: Vec<std::sync::Arc<ConsensusState>> = vec![
+        // Test with different decisions (should fail)
+        let bad_states: Vec<std::sync::Arc<ConsensusState>> = vec![
+            std::sync::Arc::new(ConsensusState {
+                role: NodeRole::Decided,
+                proposed_value: Some(Value::V0),
+                votes_received: HashMap::new(),
+                decided_value: Some(Value::V0),
+                ..ConsensusState::default()
+            }),
+            std::sync::Arc::new(ConsensusState {
+                role: NodeRole::Decided,
+                proposed_value: Some(Value::V1),
+                votes_received: HashMap::new(),
+                decided_value: Some(Value::V1),
+                ..ConsensusState::default()
+            }),
+        ];
+        assert!(!check_agreement(&bad_states), "Different values should fail agreement");
+    }
+
+    #[test]
+    fn test_agreement_among_honest_nodes() {
+        let decided = |value| std::sync::Arc::new(ConsensusState { decided_value: Some(value), ..ConsensusState::default() });
+        // Node 3 is Byzantine and "decided" a value nobody else did
+        let states = vec![decided(Value::V0), decided(Value::V0), decided(Value::V0), decided(Value::V2)];
+        let honest: HashSet<usize> = [0, 1, 2].into_iter().collect();
+
+        assert!(!check_agreement(&states));
+        assert!(check_agreement_among(&states, &honest));
+        assert!(!check_agreement_among(&states, &[1, 3].into_iter().collect()));
+        assert!(check_agreement_among(&states, &HashSet::new()), "nobody left to disagree");
+    }
+
+    #[test]
+    fn test_quorum_larger_than_cluster_is_rejected() {
+        let config = SimConfig { quorum: Some(4), ..SimConfig::default() };
+        let problems = config.try_build_model().err().expect("quorum 4 of 3 nodes is unreachable");
+        assert!(problems.contains(&"quorum 4 exceeds node count 3".to_string()));
+        assert!(SimConfig::default().try_build_model().is_ok());
+
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids).with_quorum_policy(Threshold(4));
+        assert_eq!(
+            actor.validate_quorums(),
+            Err("quorum 4 exceeds the cluster's total stake 3, so no value can ever be decided".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sim_config_validate() {
+        assert_eq!(SimConfig::default().validate(), Ok(()));
+
+        let config = SimConfig::from_json(r#"{"nodes": 4, "quorum": 3, "proposer": 3}"#).unwrap();
+        assert_eq!(config.validate(), Ok(()));
+
+        // quorum too large and proposer out of range at once
+        let config = SimConfig { quorum: Some(4), proposer: 5, ..SimConfig::default() };
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "quorum 4 exceeds node count 3".to_string(),
+                "proposer 5 out of range for 3 nodes".to_string(),
+            ])
+        );
+
+        // non-intersecting quorum, no values, too many faults
+        let config = SimConfig {
+            nodes: 4,
+            quorum: Some(2),
+            values: vec![],
+            crashed: vec![0, 1],
+            byzantine_faults: 2,
+            ..SimConfig::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "quorum 2 does not intersect: two quorums of 4 nodes can be disjoint".to_string(),
+                "values must not be empty".to_string(),
+                "fault count 4 must be less than node count 4".to_string(),
+            ])
+        );
+
+        // empty cluster trips every check that depends on node count
+        let config = SimConfig { nodes: 0, quorum: Some(0), ..SimConfig::default() };
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "nodes must be at least 1".to_string(),
+                "quorum must be at least 1".to_string(),
+                "quorum 0 does not intersect: two quorums of 0 nodes can be disjoint".to_string(),
+                "proposer 0 out of range for 0 nodes".to_string(),
+            ])
+        );
+
+        // proposal outside the value domain, bad node indices, bad loss rate
+        let config = SimConfig {
+            proposal: Value::V2,
+            values: vec![Value::V0, Value::V1],
+            crashed: vec![3],
+            partitions: vec![vec![0, 1], vec![2, 7]],
+            loss_rate: 1.5,
+            ..SimConfig::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "proposal V2 is not one of the values".to_string(),
+                "node 3 out of range for 3 nodes".to_string(),
+                "node 7 out of range for 3 nodes".to_string(),
+                "loss rate 1.5 must be between 0 and 1".to_string(),
+            ])
+        );
+
+        // A view too small for the quorum, and one without its own node
+        let config = SimConfig {
+            quorum: Some(2),
+            views: BTreeMap::from([(0, vec![0]), (1, vec![0, 2])]),
+            ..SimConfig::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "quorum 2 exceeds the 1 nodes in node 0's view".to_string(),
+                "node 1's view leaves out node 1 itself".to_string(),
+            ])
+        );
+        assert_eq!(SimConfig { views: BTreeMap::from([(0, vec![0, 1])]), ..SimConfig::default() }.validate(), Ok(()));
+
+        assert!(SimConfig::from_json(r#"{"nodes": "three"}"#).is_err(), "type errors should be rejected");
+        assert!(SimConfig::from_json(r#"{"nodez": 3}"#).is_err(), "unknown fields should be rejected");
+
+        // Configs from before `crashed` still load, crashing the last nodes
+        let config = SimConfig::from_json(r#"{"nodes": 4, "crash_faults": 1, "crashed": [3]}"#).unwrap();
+        assert_eq!(config.crashed_nodes(), vec![3, 2]);
+        assert!(serde_json::to_string(&config).unwrap().contains(r#""crash_faults":1"#));
+        assert!(!serde_json::to_string(&SimConfig::default()).unwrap().contains("crash_faults"), "left out while zero");
+    }
+
+    /// Two commits for different values from a misbehaving leader
+    fn split_commit_trace() -> Vec<DeliveryRecord> {
+        vec![
+            DeliveryRecord { src: Id::from(0), dst: Id::from(1), msg: ConsensusMsg::Commit { value: Value::V0, term: 0, slot: 0, certificate: BTreeSet::from([Id::from(0), Id::from(1)]) } },
+            DeliveryRecord { src: Id::from(0), dst: Id::from(2), msg: ConsensusMsg::Commit { value: Value::V1, term: 0, slot: 0, certificate: BTreeSet::from([Id::from(0), Id::from(2)]) } },
+        ]
+    }
+
+    #[test]
+    fn test_replay_reproduces_states() {
+        let states = replay(&SimConfig::default(), &split_commit_trace());
+        assert_eq!(states[0].decided_value, None);
+        assert_eq!(states[1].decided_value, Some(Value::V0));
+        assert_eq!(states[2].decided_value, Some(Value::V1));
+        assert!(!check_agreement(&states));
+    }
+
+    #[test]
+    fn test_replay_trace_reconstructs_exported_run() {
+        let result = SimConfig::default().build_model().checker().spawn_bfs().join();
+        let path = result.discovery("Progress").expect("a decision is reachable");
+        let expected: Vec<ConsensusState> = path.last_state().actor_states.iter().map(|s| (**s).clone()).collect();
+
+        let exported = serde_json::to_string(&trace_steps(path)).unwrap();
+        let steps: Vec<TraceStep> = serde_json::from_str(&exported).unwrap();
+        let trace = deliveries_from_trace(&steps);
+        assert!(!trace.is_empty());
+        assert_eq!(replay_trace(&trace), expected);
+
+        let far = DeliveryRecord { src: Id::from(4), dst: Id::from(0), msg: ConsensusMsg::StateRequest };
+        assert_eq!(SimConfig::for_trace(&[far]).nodes, 5);
+    }
+
+    #[test]
+    fn test_render_repro_matches_fixture() {
+        // tests/repro_agreement.rs is compiled and run by cargo as an integration
+        // test, so keeping it in sync proves the generated source builds and reproduces
+        let rendered = render_repro_test(&SimConfig::default(), "Agreement", &split_commit_trace()).unwrap();
+        assert_eq!(rendered, include_str!("../tests/repro_agreement.rs"));
+        assert!(render_repro_test(&SimConfig::default(), "Progress", &[]).is_none());
+    }
+
+    #[test]
+    fn test_loopback_self_vote_counted_once() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids).with_include_self(true);
+        let me = Id::from(0);
+        let mut state = Cow::Owned(ConsensusState {
+            role: NodeRole::Candidate,
+            proposed_value: Some(Value::V0),
+            votes_received: HashMap::new(),
+            decided_value: None,
+            ..ConsensusState::default()
+        });
+
+        // Loopback hands the proposer its own vote, possibly more than once
+        for _ in 0..2 {
+            let mut out = Out::new();
+            actor.on_msg(me, &mut state, me, ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 }, &mut out);
+            assert!(out.is_empty(), "self vote alone must not reach quorum");
+        }
+        assert_eq!(state.votes_received[&Value::V0].iter().filter(|&&v| v == me).count(), 1);
+        assert_eq!(state.role, NodeRole::Candidate, "1 of 3 votes isn't quorum");
+
+        // One real peer vote completes the majority, and the commit loops back to us too
+        let mut out = Out::new();
+        actor.on_msg(me, &mut state, Id::from(1), ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 }, &mut out);
+        assert_eq!(state.votes_received[&Value::V0].len(), 2);
+        assert_eq!(state.role, NodeRole::Decided);
+        assert_eq!(out.len(), 3, "commit goes to all peers including ourselves");
+    }
+
+    #[test]
+    fn test_loopback_proposer_votes_like_a_follower() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let model = |include_self| {
+            ActorModel::new((), ())
+                .actor(ConsensusActor::new(peer_ids.clone()).with_include_self(include_self))
+                .actors((1..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_include_self(include_self)))
+                .init_network(Network::new_unordered_nonduplicating([]))
+                .property(Expectation::Always, "Agreement", |_, state| {
+                    check_agreement(&state.actor_states)
+                })
+                .property(Expectation::Sometimes, "all decided", |_, state| {
+                    all_decided(&state.actor_states)
+                })
+        };
+        let baseline = model(false).checker().spawn_bfs().join();
+        let result = model(true).checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none());
+        // One extra Propose and Vote per round shouldn't blow up the search
+        assert!(
+            result.unique_state_count() <= 4 * baseline.unique_state_count(),
+            "{} states with loopback vs {} without",
+            result.unique_state_count(),
+            baseline.unique_state_count()
+        );
+
+        let path = result.discovery("all decided").expect("loopback cluster should decide");
+        for state in &path.last_state().actor_states {
+            assert_eq!((state.role, state.decided_value), (NodeRole::Decided, Some(Value::V0)));
+        }
+        // The proposer's vote came back through the network like everyone else's
+        let leader = &path.last_state().actor_states[0];
+        assert!(leader.votes_received[&Value::V0].contains(&Id::from(0)));
+    }
+
+    #[test]
+    fn test_majority_stakeholder_is_a_quorum_alone() {
+        // Node 0 holds 3 of 5 stake, so its own vote decides; node 1 racing
+        // for V1 can't outweigh it
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = |proposal| {
+            ConsensusActor::new(peer_ids.clone())
+                .with_leader(None)
+                .with_weights(vec![3, 1, 1])
+                .with_proposal(proposal)
+        };
+        assert_eq!(actor(Value::V0).quorum_size(), 3, "majority of 5 stake");
+        assert!(actor(Value::V0).has_quorum(&HashSet::from([Id::from(0)])));
+        assert!(!actor(Value::V0).has_quorum(&HashSet::from([Id::from(1), Id::from(2)])));
+
+        let model = ActorModel::new((), ())
+            .actors([actor(Value::V0), actor(Value::V1)])
+            .actor(ConsensusActor::new(peer_ids.clone()).with_leader(None).with_weights(vec![3, 1, 1]))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "Agreement", |_, state| {
+                check_agreement(&state.actor_states)
+            })
+            .property(Expectation::Always, "only V0", |_, state| {
+                state.actor_states.iter().all(|s| s.decided_value.is_none_or(|v| v == Value::V0))
+            })
+            .property(Expectation::Sometimes, "decides", |_, state| {
+                has_decision(&state.actor_states)
+            });
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none());
+        assert!(result.discovery("only V0").is_none(), "the minority stake decided");
+        // Node 0 commits at startup and records the decision right away
+        let path = result.discovery("decides").expect("node 0 decides alone");
+        assert!(path.into_actions().is_empty());
+    }
+
+    #[test]
+    fn test_split_votes_reach_no_quorum() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids).with_proposal(Value::V0);
+        let mut out = Out::new();
+        let mut state = Cow::Owned(actor.on_start(Id::from(0), &mut out));
+
+        // Our own V0 vote, then one vote each for V1 and V2: 1-1-1
+        for (voter, value) in [(1, Value::V1), (2, Value::V2)] {
+            let mut out = Out::new();
+            actor.on_msg(Id::from(0), &mut state, Id::from(voter), ConsensusMsg::Vote { value, term: 0, slot: 0 }, &mut out);
+            assert!(out.is_empty(), "a split vote must not trigger a commit");
+        }
+        for value in Value::ALL {
+            assert_eq!(state.votes_received[&value].len(), 1, "{:?} should hold one vote", value);
+            assert!(!actor.has_quorum_for(&state.votes_received, value));
+        }
+        assert_eq!(state.role, NodeRole::Candidate);
+
+        // Node 2 switches to V1, giving it a quorum, but we never bid V1
+        let mut out = Out::new();
+        let vote = ConsensusMsg::Vote { value: Value::V1, term: 0, slot: 0 };
+        actor.on_msg(Id::from(0), &mut state, Id::from(2), vote, &mut out);
+        assert!(actor.has_quorum_for(&state.votes_received, Value::V1));
+        assert_eq!(state.role, NodeRole::Candidate, "can't lead for someone else's value");
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_single_node_cluster_decides_alone() {
+        let actor = ConsensusActor::new(vec![Id::from(0)]);
+        let mut out = Out::new();
+        let state = actor.on_start(Id::from(0), &mut out);
+        // Own vote is a quorum of one, so it leads straight away...
+        assert_eq!(state.votes_received[&Value::V0], [Id::from(0)].into_iter().collect());
+        assert_eq!(state.certificate, [Id::from(0)].into_iter().collect());
+
+        // ...and decides on the spot, with nobody to send a Commit to
+        assert_eq!((state.role, state.decided_value), (NodeRole::Decided, Some(Value::V0)));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_scenario_healthy_cluster_decides() {
+        let summary = Scenario::new(3).proposer(0, Value::V1).run_check();
+        assert!(summary.violations.is_empty(), "{:?}", summary.violations);
+        assert!(summary.progress);
+        assert!(summary.unique_states > 1);
+    }
+
+    #[test]
+    fn test_scenario_crash_and_partition_stay_safe() {
+        // The proposer's own vote plus one follower is still a majority
+        let crashed = Scenario::new(3).proposer(0, Value::V0).crash(2).run_check();
+        assert!(crashed.violations.is_empty());
+        assert!(crashed.progress);
+
+        let partitioned = Scenario::new(3)
+            .proposer(0, Value::V0)
+            .partition(&[&[0, 1], &[2]])
+            .loss(0.1)
+            .run_check();
+        assert!(partitioned.violations.is_empty());
+        assert!(partitioned.progress, "the majority side decides");
+
+        // Alone in its partition, the proposer can't reach anyone
+        let isolated = Scenario::new(3)
+            .proposer(0, Value::V0)
+            .partition(&[&[0], &[1, 2]])
+            .run_check();
+        assert!(isolated.violations.is_empty());
+        assert!(!isolated.progress);
+    }
+
+    #[test]
+    fn test_scenario_tolerates_minority_crash() {
+        let summary = Scenario::new(5).proposer(0, Value::V2).crash(4).loss(0.5).run_check();
+        assert!(summary.violations.is_empty());
+        assert!(summary.progress, "3 remaining followers still form a quorum");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid scenario")]
+    fn test_scenario_rejects_invalid_config() {
+        Scenario::new(3).crash(5).run_check();
+    }
+
+    #[test]
+    fn test_check_report_from_built_config() {
+        let config = Scenario::new(3)
+            .proposer(0, Value::V1)
+            .quorum(2)
+            .values(&[Value::V0, Value::V1])
+            .search(Search::Dfs, None)
+            .build();
+        let report = config.check_report();
+        assert!(report.states_explored > 0);
+        assert!(SafetyProperty::ALL.iter().all(|p| report.properties[p.name()] == Verdict::Pass));
+        assert_eq!(report.properties["Progress"], Verdict::Pass);
+        assert!(report.counterexamples.is_empty());
+        assert!(report.decision_depth.is_some() && !report.messages.is_empty());
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""search":"dfs""#));
+        assert_eq!(SimConfig::from_json(&json).unwrap(), config);
+
+        let shallow = SimConfig { max_depth: Some(1), ..config }.check_report();
+        assert!(shallow.states_explored < report.states_explored);
+    }
+
+    #[test]
+    fn test_properties_read_model_config() {
+        let config = SimConfig { nodes: 4, crashed: vec![3], ..SimConfig::default() };
+        let model = config.build_model();
+        assert_eq!(model.cfg.honest(), (0..3).collect());
+        assert_eq!((model.cfg.weights.len(), model.cfg.quorum), (4, 3));
+
+        let model = model
+            .property(Expectation::Always, "OneStatePerNode", |model, state| {
+                state.actor_states.len() == model.cfg.nodes
+            })
+            .property(Expectation::Sometimes, "HonestDecided", |model, state| {
+                model.cfg.honest().iter().all(|&i| state.actor_states[i].decided_value.is_some())
+            });
+        let result = model.checker().spawn_bfs().join();
+        assert!(result.discovery("OneStatePerNode").is_none());
+        assert!(result.discovery("HonestDecided").is_some(), "the three live nodes can still decide");
+    }
+
+    #[test]
+    fn test_network_matrix_reports_each_network() {
+        let config = Scenario::new(3).values(&[Value::V0, Value::V1]).build();
+        let matrix = network_matrix(&config);
+        let kinds: Vec<NetworkKind> = matrix.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, NetworkKind::ALL);
+        for (kind, report) in &matrix {
+            assert!(report.states_explored > 0, "{} explored nothing", kind.name());
+            assert!(!report.failed("Agreement") && !report.failed("Validity"), "{} broke safety", kind.name());
+        }
+        // Redelivery only adds interleavings
+        assert!(matrix[2].1.states_explored > matrix[1].1.states_explored);
+    }
+
+    #[test]
+    fn test_size_sweep_csv() {
+        let config = Scenario::new(3).values(&[Value::V0]).build();
+        let mut csv = sweep_csv_header();
+        for (sized, report) in size_sweep(&config, 2..=4) {
+            csv.push_str(&sweep_csv_row(&sized, &report));
+        }
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "nodes,states_explored,quorum,elapsed_secs,Agreement,Validity,TotalVotesBound,SameViewQuorum,\
+             Integrity,SingleLeaderPerTerm,LeaderCommitsProposal,NoSafetyTrap,CrashedNeverVote,Progress"
+        );
+        assert_eq!(lines.len(), 4, "one row per cluster size");
+        for (line, n) in lines[1..].iter().zip(2..) {
+            let cells: Vec<&str> = line.split(',').collect();
+            assert_eq!(cells.len(), lines[0].split(',').count());
+            assert_eq!(cells[0], n.to_string());
+            assert_eq!(cells[2], (n / 2 + 1).to_string(), "majority quorum");
+            assert!(cells[1].parse::<usize>().unwrap() > 0);
+            assert!(cells[3].parse::<f64>().is_ok(), "{}", cells[3]);
+            assert!(cells[4..].iter().all(|&v| v == "pass"), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_check_report_is_timed() {
+        let report = SimConfig::default().check_report_with(1);
+        assert!(report.duration > Duration::ZERO, "a real search takes some time");
+        assert!(report.states_per_sec() > 0.0);
+        assert_eq!(CheckReport::default().states_per_sec(), 0.0);
+    }
+
+    #[test]
+    fn test_partial_membership_view() {
+        // Node 0 doesn't know about node 2, so it needs both of {0, 1} while
+        // the rival at 2 needs 2 of 3 as usual. Any two of those quorums
+        // share node 1, which only votes once, so Agreement holds - but node
+        // 2 can't hear a Commit from a node that doesn't know it exists.
+        let one_missing = Scenario::new(3).proposer(0, Value::V0).rival(2, Value::V1).view(0, &[0, 1]);
+        assert_eq!(one_missing.config().actors()[0].quorum_size(), 2);
+        let summary = one_missing.run_check();
+        assert!(summary.violations.is_empty(), "violated {:?}", summary.violations);
+        assert!(summary.progress);
+        assert!(find_stuck_run(one_missing.config().build_model()).is_some(), "node 2 is never told");
+
+        // Shrinking a view shrinks that node's quorum: node 0 settles for 2
+        // of {0, 1, 2} while the rival at 4 wants 3 of 5, and {0, 1} and
+        // {2, 3, 4} don't overlap
+        let two_missing = Scenario::new(5).proposer(0, Value::V0).rival(4, Value::V1).view(0, &[0, 1, 2]);
+        assert!(!two_missing.config().is_symmetric());
+        let summary = two_missing.run_check();
+        assert!(summary.violations.contains(&"Agreement"), "disjoint quorums should split the cluster");
+    }
+
+    #[test]
+    fn test_depth_bound_marks_report_not_exhaustive() {
+        let small = SimConfig { values: vec![Value::V0], ..SimConfig::default() };
+        let full = small.check_report();
+        assert!(full.exhaustive && !full.truncated);
+        assert_eq!(full.qualifier(), "(verified)");
+
+        let shallow = SimConfig { max_depth: Some(3), ..small.clone() }.check_report();
+        assert!(!shallow.exhaustive, "a 3-step bound cuts the run off before anyone decides");
+        assert!(!shallow.truncated, "no state cap involved");
+        assert_eq!(shallow.qualifier(), "(within bounds)");
+
+        // A bound the search never reaches takes nothing away
+        assert!(SimConfig { max_depth: Some(100), ..small.clone() }.check_report().exhaustive);
+
+        // So do caps the model itself puts on duplicates and withheld messages
+        assert!(!SimConfig { max_dupes: Some(2), ..small.clone() }.check_report().exhaustive);
+        assert!(!SimConfig { adversary: true, ..small }.check_report().exhaustive);
+        assert!(hit_depth_bound(3, Some(3)) && !hit_depth_bound(2, Some(3)) && !hit_depth_bound(9, None));
+    }
+
+    #[test]
+    fn test_only_bfs_counterexamples_are_called_shortest() {
+        let small = SimConfig { values: vec![Value::V0], ..SimConfig::default() };
+        assert_eq!(small.check_report().counterexample_label(), "Shortest counterexample");
+        let dfs = SimConfig { search: Search::Dfs, ..small }.check_report();
+        assert_eq!(dfs.counterexample_label(), "Counterexample");
+    }
+
+    #[test]
+    fn test_exit_code_follows_safety_verdicts() {
+        let mut report = CheckReport::default();
+        assert_eq!(report.exit_code(), EXIT_OK, "nothing checked, nothing violated");
+        report.properties.insert("Agreement", Verdict::Pass);
+        report.properties.insert("Progress", Verdict::Pending);
+        report.properties.insert("Termination", Verdict::Fail);
+        assert_eq!(report.exit_code(), EXIT_OK, "only safety violations fail the run");
+        report.properties.insert("Integrity", Verdict::Fail);
+        assert_eq!(report.exit_code(), EXIT_VIOLATION);
+
+        // Every Always property the model checks is a safety verdict
+        let model = SimConfig::default().build_model();
+        let always: Vec<&str> = model
+            .properties
+            .iter()
+            .filter(|p| p.expectation == Expectation::Always)
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(always, SafetyProperty::ALL.map(|p| p.name()));
+        let crashed_voter = CheckReport {
+            properties: [("CrashedNeverVote", Verdict::Fail)].into_iter().collect(),
+            ..CheckReport::default()
+        };
+        assert_eq!(crashed_voter.exit_code(), EXIT_VIOLATION);
+
+        let split = Scenario::new(5).proposer(0, Value::V0).rival(4, Value::V1).view(0, &[0, 1, 2]);
+        assert_eq!(split.config().check_report().exit_code(), EXIT_VIOLATION);
+    }
+
+    #[test]
+    fn test_state_cap_marks_report_incomplete() {
+        let full = SimConfig { values: vec![Value::V0], ..SimConfig::default() }.check_report();
+        assert!(!full.truncated);
+
+        // 7 nodes over two values is 87446 states; the checker only looks at
+        // the cap between blocks of 1500 per thread, so it stops a few
+        // thousand states in rather than at 10
+        let capped = SimConfig {
+            nodes: 7,
+            values: vec![Value::V0, Value::V1],
+            max_states: Some(10),
+            ..SimConfig::default()
+        };
+        let report = capped.check_report();
+        assert!(report.truncated, "a 10-state cap should cut the search short");
+        assert!(report.states_explored < 20_000, "explored {} states", report.states_explored);
+        assert_eq!(serde_json::to_value(&report).unwrap()["truncated"], true);
+        assert!(!hit_state_cap(9, Some(10)) && !hit_state_cap(1_000_000, None));
+    }
+
+    #[test]
+    fn test_bounded_duplication_stays_safe() {
+        let config = |max_dupes| SimConfig { max_dupes, ..SimConfig::default() };
+
+        let twice = config(Some(2)).run_check();
+        assert!(twice.violations.is_empty(), "{:?}", twice.violations);
+        assert!(twice.progress);
+
+        // The plain protocol ignores repeats, which the checker prunes, but a
+        // repeated Commit is acknowledged again. Each extra allowed delivery
+        // then adds states, and the space stays finite.
+        let acking = |max_dupes| {
+            let mut model = config(max_dupes).build_model();
+            model.actors = model.actors.into_iter().map(|actor| actor.with_commit_acks(true)).collect();
+            model.checker().spawn_bfs().join()
+        };
+        let (once, twice) = (acking(Some(1)), acking(Some(2)));
+        assert!(twice.discovery("Agreement").is_none());
+        assert!(
+            once.unique_state_count() < twice.unique_state_count(),
+            "{} states once, {} twice",
+            once.unique_state_count(),
+            twice.unique_state_count()
+        );
+        assert!(twice.unique_state_count() < 2_000, "explored {}", twice.unique_state_count());
+
+        let mut counts = DeliveryCounts::new(Some(2));
+        let msg = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+        for _ in 0..3 {
+            let env = Envelope { src: Id::from(1), dst: Id::from(0), msg: &msg };
+            counts = DeliveryCounts::record_in(&(), &counts, env).unwrap();
+        }
+        assert!(!counts.within_limit(), "third delivery exceeds max_dupes = 2");
+        let env = Envelope { src: Id::from(1), dst: Id::from(0), msg: &msg };
+        assert!(DeliveryCounts::record_in(&(), &DeliveryCounts::new(None), env).is_none());
+    }
+
+    #[test]
+    fn test_network_kinds_compared() {
+        let unordered = SimConfig { rival: Some((1, Value::V1)), ..SimConfig::default() };
+        let ordered = SimConfig { ordered: true, ..unordered.clone() };
+        let duplicating = SimConfig { max_dupes: Some(2), ..unordered.clone() };
+        let (a, b, c) = (unordered.run_check(), ordered.run_check(), duplicating.run_check());
+        for summary in [&a, &b, &c] {
+            assert!(summary.violations.is_empty(), "{:?}", summary.violations);
+            assert!(summary.progress);
+        }
+        // Not fewer, as FIFO channels might suggest: a stale Vote at the head
+        // of an ordered channel has to be delivered to get past it, while the
+        // unordered network can leave it in flight forever
+        assert!(b.unique_states >= a.unique_states, "ordered {} vs unordered {}", b.unique_states, a.unique_states);
+
+        let both = SimConfig { max_dupes: Some(2), ..ordered };
+        assert!(both.validate().is_err());
+    }
+
+    #[test]
+    fn test_decision_stability() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        // Every path of a contended run keeps its decisions
+        let stable = std::sync::Arc::new(AtomicBool::new(true));
+        let flag = stable.clone();
+        let config = SimConfig { rival: Some((1, Value::V1)), ..SimConfig::default() };
+        config
+            .build_model()
+            .checker()
+            .visitor(move |path: ConsensusPath| {
+                if !check_decision_stability(&path.into_states()) {
+                    flag.store(false, Ordering::Relaxed);
+                }
+            })
+            .spawn_bfs()
+            .join();
+        assert!(stable.load(Ordering::Relaxed), "a node changed its mind");
+
+        // Synthetic paths where node 1 flips, forgets or demotes its decision
+        let init = config.build_model().init_states().remove(0);
+        let with_node_1 = |decided_value, role| {
+            let mut state = init.clone();
+            state.actor_states[1] = std::sync::Arc::new(ConsensusState { decided_value, role, ..ConsensusState::default() });
+            state
+        };
+        let decided = with_node_1(Some(Value::V0), NodeRole::Decided);
+        assert!(check_decision_stability(&[init.clone(), decided.clone(), decided.clone()]));
+        assert!(!check_decision_stability(&[decided.clone(), with_node_1(Some(Value::V1), NodeRole::Decided)]));
+        assert!(!check_decision_stability(&[decided.clone(), with_node_1(None, NodeRole::Follower)]));
+        assert!(!check_decision_stability(&[decided, with_node_1(Some(Value::V0), NodeRole::Leader)]));
+    }
+
+    #[test]
+    fn test_integrity_flags_unproposed_decision() {
+        let proposer = ConsensusState {
+            role: NodeRole::Leader,
+            proposed_value: Some(Value::V0),
+            ..ConsensusState::default()
+        };
+        let follower = |decided| ConsensusState {
+            role: NodeRole::Decided,
+            proposed_value: Some(Value::V0),
+            decided_value: Some(decided),
+            ..ConsensusState::default()
+        };
+        let honest: Vec<_> = [proposer.clone(), follower(Value::V0)].into_iter().map(std::sync::Arc::new).collect();
+        assert!(check_integrity(&honest));
+
+        // V2 is in the domain, so Validity without a model lets it through
+        let forged: Vec<_> = [proposer, follower(Value::V2)].into_iter().map(std::sync::Arc::new).collect();
+        let cfg = SimConfig { nodes: 2, ..SimConfig::default() }.model_config();
+        assert!(SafetyProperty::Validity.eval(&cfg, &forged));
+        assert!(!SafetyProperty::Integrity.eval(&cfg, &forged), "nobody proposed V2");
+    }
+
+    #[test]
+    fn test_leader_commits_proposal() {
+        // Node 0 collected a quorum for V0, the value it proposed
+        let leader = |decided| {
             std::sync::Arc::new(ConsensusState {
                 role: NodeRole::Decided,
                 proposed_value: Some(Value::V0),
-                votes_received: HashSet::new(),
-                decided_value: Some(Value::V0),
-            }),
+                decided_value: Some(decided),
+                votes_received: [(Value::V0, [Id::from(0), Id::from(1)].into())].into(),
+                ..ConsensusState::default()
+            })
+        };
+        // A rival put V1 forward, so Integrity has nothing to object to
+        let rival = std::sync::Arc::new(ConsensusState {
+            proposed_value: Some(Value::V1),
+            ..ConsensusState::default()
+        });
+        let follower = std::sync::Arc::new(ConsensusState::default());
+        assert!(check_leader_commits_proposal(&[leader(Value::V0), rival.clone(), follower.clone()], 2));
+
+        let swapped = [leader(Value::V1), rival, follower];
+        let cfg = SimConfig::default().model_config();
+        assert!(SafetyProperty::Integrity.eval(&cfg, &swapped));
+        assert!(!check_leader_commits_proposal(&swapped, 2), "proposed V0 but decided V1");
+        assert!(!SafetyProperty::LeaderCommitsProposal.eval(&cfg, &swapped));
+
+        // With an explicit quorum of 3, two voters don't make node 0 a leader
+        let strict = SimConfig { quorum: Some(3), ..SimConfig::default() }.model_config();
+        assert!(SafetyProperty::LeaderCommitsProposal.eval(&strict, &swapped));
+    }
+
+    #[test]
+    fn test_single_leader_per_term() {
+        let leader = |term| {
             std::sync::Arc::new(ConsensusState {
-                role: NodeRole::Decided,
+                role: NodeRole::Leader,
+                proposed_value: Some(Value::V0),
+                term,
+                ..ConsensusState::default()
+            })
+        };
+        assert!(check_single_leader_per_term(&[leader(0), leader(1)]), "a newer round may have its own leader");
+        assert!(!check_single_leader_per_term(&[leader(1), leader(1)]), "two leaders in term 1");
+    }
+
+    #[test]
+    fn test_stale_view_votes_do_not_justify_decision() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids);
+        let mut state = Cow::Owned(ConsensusState {
+            role: NodeRole::Candidate,
+            proposed_value: Some(Value::V0),
+            term: 1,
+            ..ConsensusState::default()
+        });
+
+        // Votes left over from view 0 arrive while we're campaigning in view 1
+        for voter in 1..3 {
+            let mut out = Out::new();
+            let stale = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+            actor.on_msg(Id::from(0), &mut state, Id::from(voter), stale, &mut out);
+            assert!(out.is_empty(), "stale vote must not trigger a commit");
+        }
+        assert_eq!(state.role, NodeRole::Candidate);
+        assert!(state.votes_received.is_empty());
+        assert!(check_same_view_quorum(&[std::sync::Arc::new(state.into_owned())]));
+
+        // Node 0 of 5 has itself and node 1 in view 0 when a Propose from
+        // view 1 moves it on, dropping that tally
+        let actor = ConsensusActor::new((0..5).map(Id::from).collect());
+        let mut state = Cow::Owned(actor.on_start(Id::from(0), &mut Out::new()));
+        let vote = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+        actor.on_msg(Id::from(0), &mut state, Id::from(1), vote, &mut Out::new());
+        let tally = state.votes_received.clone();
+        let propose = ConsensusMsg::Propose { value: Value::V1, term: 1, slot: 0 };
+        actor.on_msg(Id::from(0), &mut state, Id::from(2), propose, &mut Out::new());
+        assert_eq!((state.term, tally.len()), (1, 1));
+        assert!(check_same_view_quorum(&[std::sync::Arc::new(state.clone().into_owned())]));
+
+        // A handler that kept the tally across the view change is flagged
+        let kept = ConsensusState { votes_received: tally, ..state.into_owned() };
+        assert!(!check_same_view_quorum(&[std::sync::Arc::new(kept)]));
+    }
+
+    #[test]
+    fn test_higher_term_proposal_overrides_lower() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids).with_proposal(Value::V0);
+        let me = Id::from(0);
+        let mut out = Out::new();
+        let mut state: Cow<ConsensusState> = Cow::Owned(actor.on_start(me, &mut out));
+        assert_eq!(state.role, NodeRole::Candidate);
+
+        // Node 1 starts a newer round while ours is still collecting votes
+        let mut out = Out::new();
+        actor.on_msg(me, &mut state, Id::from(1), ConsensusMsg::Propose { value: Value::V1, term: 1, slot: 0 }, &mut out);
+        assert_eq!(state.term, 1);
+        assert_eq!(state.role, NodeRole::Follower);
+        assert_eq!(state.proposed_value, Some(Value::V1));
+        assert!(state.votes_received.is_empty(), "term 0 votes are dropped");
+        assert_eq!(out.len(), 1, "we vote in the new round");
+
+        // The old round can't pull us back
+        let mut out = Out::new();
+        actor.on_msg(me, &mut state, Id::from(2), ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 }, &mut out);
+        actor.on_msg(me, &mut state, Id::from(2), ConsensusMsg::Commit {
+            value: Value::V0,
+            term: 0,
+            slot: 0,
+            certificate: BTreeSet::new(),
+        }, &mut out);
+        assert!(out.is_empty());
+        assert_eq!((state.term, state.decided_value), (1, None));
+    }
+
+    #[test]
+    fn test_conflicting_commit_trips_the_safety_trap() {
+        let actor = ConsensusActor::new((0..3).map(Id::from).collect());
+        let me = Id::from(2);
+        let mut state: Cow<ConsensusState> = Cow::Owned(actor.on_start(me, &mut Out::new()));
+        let commit = |value| ConsensusMsg::Commit { value, term: 0, slot: 0, certificate: BTreeSet::from([Id::from(0), Id::from(1)]) };
+        actor.on_msg(me, &mut state, Id::from(0), commit(Value::V0), &mut Out::new());
+        actor.on_msg(me, &mut state, Id::from(0), commit(Value::V0), &mut Out::new());
+        assert!(!state.safety_violation, "a repeat of our decision is harmless");
+        assert!(check_no_safety_trap(&[std::sync::Arc::new(state.clone().into_owned())]));
+
+        actor.on_msg(me, &mut state, Id::from(1), commit(Value::V1), &mut Out::new());
+        assert!(state.safety_violation);
+        assert_eq!(state.decided_value, Some(Value::V0), "the decision stands");
+        assert!(!check_no_safety_trap(&[std::sync::Arc::new(state.into_owned())]));
+
+        // An equivocator lets both candidates commit, and the checker finds a
+        // node holding one decision when the other's Commit lands
+        let result = byzantine_split_model(3, 1, 2).checker().spawn_bfs().join();
+        let path = result.discovery("NoSafetyTrap").expect("the split should trip the trap");
+        assert!(path.last_state().actor_states.iter().any(|s| s.safety_violation));
+    }
+
+    #[test]
+    fn test_safety_properties_hold_on_honest_model() {
+        let result = SimConfig::default().build_model().checker().spawn_bfs().join();
+        assert!(result.discovery("Progress").is_some(), "honest model should decide");
+        for property in SafetyProperty::ALL {
+            assert!(result.discovery(property.name()).is_none(), "{} violated", property.name());
+            assert_eq!(SafetyProperty::from_name(property.name()), Some(property));
+        }
+        assert_eq!(SafetyProperty::from_name("Progress"), None, "liveness isn't a safety property");
+    }
+
+    #[test]
+    fn test_leader_leads_to_decision() {
+        let check = |model: ConsensusModel| {
+            model
+                .property(Expectation::Eventually, "LeaderLeadsToDecision", leader_leads_to_decision)
+                .checker()
+                .spawn_bfs()
+                .join()
+                .discovery("LeaderLeadsToDecision")
+        };
+
+        assert!(check(SimConfig::default().build_model()).is_none(), "reliable network always follows through");
+
+        // A Leader decides as it commits, but with two phases it first waits
+        // for Promises, so dropping both Prepares strands it. Only the verdict
+        // is checked: Stateright keeps overwriting an Eventually counterexample
+        // with later terminal states, so the reported path needn't end at the
+        // stranded one.
+        let mut lossy = SimConfig {
+            loss_rate: 0.1,
+            ..SimConfig::default()
+        }
+        .build_model();
+        lossy.actors = lossy.actors.into_iter().map(|actor| actor.with_two_phase(true)).collect();
+        assert!(check(lossy).is_some(), "loss can strand a Leader");
+    }
+
+    #[test]
+    fn test_termination_on_reliable_network() {
+        let terminates = |config: SimConfig| {
+            config
+                .build_model()
+                .property(Expectation::Eventually, "Termination", termination)
+                .checker()
+                .spawn_bfs()
+                .join()
+                .discovery("Termination")
+                .is_none()
+        };
+
+        assert!(terminates(SimConfig::default()));
+        assert!(terminates(SimConfig {
+            rival: Some((1, Value::V1)),
+            ..SimConfig::default()
+        }), "contention still ends with everyone decided");
+        assert!(terminates(SimConfig {
+            crashed: vec![2],
+            ..SimConfig::default()
+        }), "crashed nodes aren't waited for");
+    }
+
+    #[test]
+    fn test_loss_breaks_termination_not_agreement() {
+        let lossy = SimConfig {
+            rival: Some((1, Value::V1)),
+            loss_rate: 0.5,
+            ..SimConfig::default()
+        };
+        let result = lossy
+            .build_model()
+            .property(Expectation::Eventually, "Termination", termination)
+            .checker()
+            .spawn_bfs()
+            .join();
+        assert!(result.discovery("Termination").is_some(), "a dropped message can strand a run");
+        assert!(result.discovery("Agreement").is_none(), "dropping messages must not split decisions");
+    }
+
+    #[test]
+    fn test_adversary_withholding_one_message_blocks_termination() {
+        let adversary = SimConfig { adversary: true, ..SimConfig::default() };
+        let result = adversary
+            .build_model()
+            .property(Expectation::Eventually, "Termination", termination)
+            .checker()
+            .spawn_bfs()
+            .join();
+        assert!(result.discovery("Termination").is_some(), "one withheld message should strand a run");
+        assert!(result.discovery("Agreement").is_none(), "Agreement violated");
+        assert!(result.discovery("Validity").is_none(), "Validity violated");
+
+        let path = find_stuck_run(adversary.build_model()).expect("a concrete stuck run");
+        let drops = path.clone().into_actions().iter().filter(|a| matches!(a, ActorModelAction::Drop(_))).count();
+        assert_eq!(drops, 1, "the adversary only gets to withhold one message");
+        let model = adversary.build_model();
+        assert!(is_terminal(&model, path.last_state()) && !termination(&model, path.last_state()));
+
+        assert!(find_stuck_run(SimConfig::default().build_model()).is_none(), "a reliable network always terminates");
+    }
+
+    #[test]
+    fn test_fairness_makes_termination_meaningful() {
+        let lossy = SimConfig {
+            rival: Some((1, Value::V1)),
+            loss_rate: 0.5,
+            ..SimConfig::default()
+        };
+        let stuck = |config: &SimConfig| {
+            config
+                .build_model()
+                .property(Expectation::Eventually, "Termination", termination)
+                .checker()
+                .spawn_bfs()
+                .join()
+                .discovery("Termination")
+                .is_some()
+        };
+
+        assert!(stuck(&lossy), "unfair loss can drop the decisive message");
+        let fair = lossy.fair_schedules();
+        assert!(!stuck(&fair), "every fair schedule ends with everyone decided");
+        assert_eq!(SimConfig { loss_rate: 0.5, ..fair }, lossy, "only the loss changes");
+    }
+
+    #[test]
+    fn test_depth_bounded_dfs() {
+        let full = run_search(SimConfig::default().build_model(), Search::Bfs, None, None, 4);
+        let bounded = run_search(SimConfig::default().build_model(), Search::Dfs, Some(3), None, 4);
+        assert!(bounded.unique_state_count() > 0);
+        assert!(bounded.unique_state_count() < full.unique_state_count(), "depth bound should cut the search short");
+        assert!(bounded.max_depth() <= 3);
+    }
+
+    #[test]
+    fn test_trace_steps_round_trip_through_json() {
+        let result = SimConfig::default().build_model().checker().spawn_bfs().join();
+        let path = result.discovery("Progress").expect("honest model decides");
+        let steps = trace_steps(path.clone());
+        assert_eq!(steps.len(), path.into_vec().len());
+        assert_eq!(steps[0].action, None, "first step is the initial state");
+        assert!(steps[1..].iter().all(|step| matches!(step.action, Some(TraceAction::Deliver(_)))));
+
+        let json = serde_json::to_string(&steps).unwrap();
+        let back: Vec<TraceStep> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, steps);
+
+        // Voters come out sorted whatever order the set holds them in
+        let state = ConsensusState {
+            votes_received: HashMap::from([(Value::V0, [Id::from(2), Id::from(0), Id::from(1)].into_iter().collect())]),
+            ..ConsensusState::default()
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains(r#""votes_received":{"V0":[0,1,2]}"#), "{}", json);
+    }
+
+    #[test]
+    fn test_consensus_state_serde_round_trip() {
+        let roles = [
+            NodeRole::Follower,
+            NodeRole::Candidate,
+            NodeRole::Leader,
+            NodeRole::Decided,
+            NodeRole::Learner,
+            NodeRole::Aborted,
+        ];
+        for role in roles {
+            let state = ConsensusState {
+                role,
                 proposed_value: Some(Value::V1),
-                votes_received: HashSet::new(),
-                decided_value: Some(Value::V1),
-            }),
+                votes_received: HashMap::from([
+                    (Value::V1, [Id::from(3), Id::from(0)].into_iter().collect()),
+                    (Value::V2, [Id::from(1)].into_iter().collect()),
+                ]),
+                decided_value: (role == NodeRole::Decided).then_some(Value::V1),
+                term: 2,
+                vote_terms: [(Id::from(3), Value::V1, 2), (Id::from(0), Value::V1, 2), (Id::from(1), Value::V2, 2)]
+                    .into_iter()
+                    .collect(),
+                certificate: [Id::from(0), Id::from(3)].into_iter().collect(),
+                slot: 1,
+                decided_log: vec![Value::V0],
+                pending_commit: (role == NodeRole::Leader).then(|| ConsensusMsg::Commit {
+                    value: Value::V1,
+                    term: 2,
+                    slot: 1,
+                    certificate: [Id::from(0), Id::from(3)].into_iter().collect(),
+                }),
+                commit_acks: [Id::from(3)].into_iter().collect(),
+                locked: Some(Value::V1),
+                promises: [Id::from(0)].into_iter().collect(),
+                rivals: [Id::from(1)].into_iter().collect(),
+                stalls: u32::from(role == NodeRole::Aborted),
+                has_proposed_in_term: role != NodeRole::Follower,
+                commits_received: HashMap::from([(Value::V1, [Id::from(0), Id::from(3)].into_iter().collect())]),
+                safety_violation: role == NodeRole::Decided,
+                deferred: [(Id::from(1), ConsensusMsg::Propose { value: Value::V2, term: 2, slot: 2 })].into_iter().collect(),
+                vote_log: vec![(Id::from(3), Value::V1), (Id::from(1), Value::V2), (Id::from(0), Value::V1)],
+                rejected: 4,
+            };
+            let json = serde_json::to_string(&state).unwrap();
+            let back: ConsensusState = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, state, "{:?} didn't survive the round trip", role);
+            assert_eq!((back.vote_log, back.rejected), (state.vote_log, state.rejected), "equality skips these");
+            assert!(json.contains(r#""votes_received":{"V1":[0,3],"V2":[1]}"#), "{}", json);
+        }
+    }
+
+    #[test]
+    fn test_build_model() {
+        let result = build_model(3, false).checker().spawn_bfs().join();
+        assert!(result.unique_state_count() > 0);
+        assert!(result.discovery("Progress").is_some());
+
+        let names = |model: ConsensusModel| model.properties().iter().map(|p| p.name).collect::<Vec<_>>();
+        assert!(!names(build_model(3, false)).contains(&"Termination"));
+        let live = names(build_model(3, true));
+        assert!(live.contains(&"Termination") && live.contains(&"LeaderLeadsToDecision"));
+    }
+
+    #[test]
+    fn test_standard_properties() {
+        let names: Vec<&str> = standard_properties().into_iter().map(|(_, name, _)| name).collect();
+        for name in ["Agreement", "Validity", "Progress"] {
+            assert!(names.contains(&name), "{} missing from {:?}", name, names);
+        }
+        let built: Vec<&str> = SimConfig::default().build_model().properties().iter().map(|p| p.name).collect();
+        assert_eq!(built, names, "build_model attaches exactly the registry");
+        assert!(liveness_properties().iter().all(|(expectation, _, _)| *expectation == Expectation::Eventually));
+    }
+
+    #[test]
+    fn test_symmetry_collapses_renamed_nodes() {
+        // Node 0 has votes from 0 and 1 while its Propose to node 2 is still
+        // in flight; swapping nodes 0 and 2 describes the same situation
+        let candidate = |me: usize| ConsensusState {
+            role: NodeRole::Candidate,
+            proposed_value: Some(Value::V0),
+            votes_received: HashMap::from([(Value::V0, [Id::from(me), Id::from(1)].into())]),
+            vote_terms: [(Id::from(me), Value::V0, 0), (Id::from(1), Value::V0, 0)].into(),
+            ..ConsensusState::default()
+        };
+        let voted = ConsensusState { proposed_value: Some(Value::V0), ..ConsensusState::default() };
+        let waiting = ConsensusState::default();
+        let propose = |src: usize, dst: usize| Envelope {
+            src: Id::from(src),
+            dst: Id::from(dst),
+            msg: ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 },
+        };
+        let model = SimConfig::default().build_model();
+        let mut a = model.init_states().remove(0);
+        let mut b = a.clone();
+        a.actor_states = [candidate(0), voted.clone(), waiting.clone()].map(std::sync::Arc::new).to_vec();
+        b.actor_states = [waiting, voted, candidate(2)].map(std::sync::Arc::new).to_vec();
+        a.network = Network::new_unordered_nonduplicating([propose(0, 2)]);
+        b.network = Network::new_unordered_nonduplicating([propose(2, 0)]);
+        assert_ne!(a, b);
+        assert_eq!(a.representative(), b.representative());
+
+        // Fewer states, same verdicts
+        let config = SimConfig { nodes: 4, ..SimConfig::default() };
+        assert!(config.is_symmetric());
+        assert!(!SimConfig { crashed: vec![2], ..config.clone() }.is_symmetric());
+        let full = run_search(config.build_model(), Search::Dfs, None, None, 4);
+        let reduced = run_symmetric_search(config.build_model(), None, None, 4);
+        assert!(reduced.unique_state_count() < full.unique_state_count());
+        assert!(reduced.discovery("Progress").is_some());
+        assert!(SafetyProperty::ALL.iter().all(|p| reduced.discovery(p.name()).is_none()));
+
+        let unsafe_quorum = SimConfig { nodes: 4, quorum: Some(1), rival: Some((1, Value::V1)), ..SimConfig::default() };
+        let reduced = run_symmetric_search(unsafe_quorum.build_model(), None, None, 4);
+        assert!(reduced.discovery("Agreement").is_some(), "reduction hid a real violation");
+    }
+
+    #[test]
+    fn test_message_counts_for_one_decision() {
+        let model = SimConfig::default().build_model();
+        let deliver = |src: usize, dst: usize, msg| ActorModelAction::Deliver {
+            src: Id::from(src),
+            dst: Id::from(dst),
+            msg,
+        };
+        let propose = ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 };
+        let vote = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+        let commit = ConsensusMsg::Commit {
+            value: Value::V0,
+            term: 0,
+            slot: 0,
+            certificate: [Id::from(0), Id::from(1)].into(),
+        };
+        // Node 2's vote is still in flight when everyone has decided
+        let path = ConsensusPath::from_actions(
+            &model,
+            model.init_states().remove(0),
+            &[
+                deliver(0, 1, propose.clone()),
+                deliver(0, 2, propose),
+                deliver(1, 0, vote),
+                deliver(0, 1, commit.clone()),
+                deliver(0, 2, commit),
+            ],
+        )
+        .expect("a plain 3-node decision");
+        assert!(all_decided(&path.last_state().actor_states));
+        assert_eq!(
+            message_counts(&model, path),
+            BTreeMap::from([("Propose", 2), ("Vote", 2), ("Commit", 2)])
+        );
+    }
+
+    #[test]
+    fn test_rejected_messages_are_counted() {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids).with_leader(None);
+        let start = actor.on_start(Id::from(1), &mut Out::new());
+        let vote = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+
+        // Followers have no use for votes
+        let (state, _) = actor.step(Id::from(1), &start, Id::from(2), &vote);
+        assert_eq!(state.rejected, 1);
+        assert_eq!(state.role, NodeRole::Follower);
+
+        // A proposal is acted on, so it isn't counted
+        let propose = ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 };
+        let (state, _) = actor.step(Id::from(1), &state, Id::from(0), &propose);
+        assert_eq!((state.rejected, state.proposed_value), (1, Some(Value::V0)));
+
+        // The checker's on_msg leaves an ignored delivery a no-op it can skip
+        let mut state = Cow::Borrowed(&start);
+        actor.on_msg(Id::from(1), &mut state, Id::from(2), vote, &mut Out::new());
+        assert!(matches!(state, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_explain_quorum_one_vote_short() {
+        // n = 4 needs 3 votes; node 0 has its own and node 2's for V0, and a
+        // stray V1 from node 1
+        let peer_ids: Vec<Id> = (0..4).map(Id::from).collect();
+        let actor = ConsensusActor::new(peer_ids).with_proposal(Value::V0);
+        let mut state = Cow::Owned(actor.on_start(Id::from(0), &mut Out::new()));
+        for (voter, value) in [(2, Value::V0), (1, Value::V1)] {
+            let vote = ConsensusMsg::Vote { value, term: 0, slot: 0 };
+            actor.on_msg(Id::from(0), &mut state, Id::from(voter), vote, &mut Out::new());
+        }
+        assert_eq!(state.role, NodeRole::Candidate);
+        assert_eq!(
+            state.explain_quorum(actor.quorum_size()),
+            "V0: 2 of 3 votes (0, 2), 1 short\nV1: 1 of 3 votes (1), 2 short\narrived: 0 V0, 2 V0, 1 V1"
+        );
+        assert_eq!(ConsensusState::<Value>::default().explain_quorum(3), "no votes yet");
+
+        // The same votes in another order are the same state, hash and all
+        let reordered = ConsensusState { vote_log: state.vote_log.iter().rev().copied().collect(), rejected: 7, ..(*state).clone() };
+        let hash = |s: &ConsensusState| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            s.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(reordered, *state);
+        assert_eq!(reordered.cmp(&state), std::cmp::Ordering::Equal);
+        assert_eq!(hash(&reordered), hash(&state));
+    }
+
+    thread_local! {
+        static CAPTURED: std::cell::RefCell<Option<Vec<String>>> = const { std::cell::RefCell::new(None) };
+    }
+
+    /// Collects log lines, but only on a thread that asked for them, so the
+    /// tests running alongside don't mix theirs in
+    struct CaptureLogger;
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            CAPTURED.with(|lines| lines.borrow().is_some())
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED.with(|lines| {
+                if let Some(lines) = lines.borrow_mut().as_mut() {
+                    lines.push(record.args().to_string());
+                }
+            });
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_simulation_logs_quorum() {
+        static LOGGER: CaptureLogger = CaptureLogger;
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+        CAPTURED.with(|lines| *lines.borrow_mut() = Some(Vec::new()));
+
+        let run = simulate(&build_model(3, false), 0, 100);
+        let lines = CAPTURED.with(|lines| lines.borrow_mut().take()).unwrap();
+        log::set_max_level(log::LevelFilter::Off);
+
+        assert!(run.last().is_some_and(|line| line.starts_with("all nodes decided")));
+        assert!(lines.iter().any(|l| l.contains("quorum reached")), "no quorum logged in {:?}", lines);
+        assert!(lines.iter().any(|l| l.contains("commit applied")));
+        assert_eq!(run, simulate(&build_model(3, false), 0, 100), "logging must not change the run");
+    }
+
+    #[test]
+    fn test_simulate_drops_every_vote_at_full_chaos() {
+        let mut model = build_model(3, false);
+        model.actors = model.actors.into_iter().map(|actor| actor.with_drop_vote_prob(1.0)).collect();
+        for seed in [0, 7, 42] {
+            let log = simulate(&model, seed, 100);
+            assert!(log.iter().any(|l| l.contains("never sent its Vote")), "{:?}", log);
+            assert!(!log.iter().any(|l| l.contains(": Vote")), "a Vote was delivered: {:?}", log);
+            assert!(!log.iter().any(|l| l.contains("Leader") || l.contains("decided:")), "{:?}", log);
+            assert!(log.last().unwrap().starts_with("no more actions"), "{:?}", log);
+            assert_eq!(log, simulate(&model, seed, 100), "seed {}", seed);
+        }
+        // The checker doesn't roll dice: the same model still decides
+        assert!(model.checker().spawn_bfs().join().discovery("Progress").is_some());
+    }
+
+    #[test]
+    fn test_propose_first_delivers_proposals_before_votes() {
+        let model = build_model(3, false);
+        // The fields after the kind match between a Propose and its Votes
+        let delivered = |log: &[String], kind: &str| -> Vec<(usize, String)> {
+            let marker = format!(": {} ", kind);
+            log.iter()
+                .enumerate()
+                .filter_map(|(i, line)| line.split_once(marker.as_str()).map(|(_, fields)| (i, fields.to_string())))
+                .collect()
+        };
+        let early_vote = |log: &[String]| {
+            let proposals = delivered(log, "Propose");
+            delivered(log, "Vote")
+                .into_iter()
+                .any(|(vote, fields)| proposals.iter().any(|(propose, f)| *propose > vote && *f == fields))
+        };
+        let mut raced = false;
+        for seed in 0..20 {
+            let log = simulate_with(&model, seed, 100, Schedule::ProposeFirst);
+            assert!(!delivered(&log, "Vote").is_empty(), "{:?}", log);
+            assert!(!early_vote(&log), "seed {}: a Vote beat its Propose: {:?}", seed, log);
+            raced |= early_vote(&simulate(&model, seed, 100));
+        }
+        assert!(raced, "some random run should deliver a Vote before its Propose");
+        assert_eq!("commit-last".parse::<Schedule>(), Ok(Schedule::CommitLast));
+    }
+
+    #[test]
+    fn test_simulate_is_reproducible_per_seed() {
+        let model = SimConfig {
+            rival: Some((1, Value::V1)),
+            ..SimConfig::default()
+        }
+        .build_model();
+        for seed in [0, 7, 42] {
+            let log = simulate(&model, seed, 50);
+            assert_eq!(log, simulate(&model, seed, 50), "seed {}", seed);
+            assert!(log[0].starts_with("1. "), "{:?}", log);
+            let last = log.last().unwrap();
+            assert!(last.contains("decided after") || last.contains("no more actions"), "{}", last);
+        }
+        let capped = simulate(&model, 0, 1);
+        assert_eq!(capped.len(), capped.iter().filter(|l| l.starts_with("    ")).count() + 2);
+        assert_eq!(capped.last().unwrap(), "stopped at the 1 step cap");
+    }
+
+    #[test]
+    fn test_decision_timeline_csv() {
+        let result = SimConfig::default().build_model().checker().spawn_bfs().join();
+        let path = result.discovery("Progress").expect("honest model decides");
+        let steps = path.clone().into_vec().len();
+
+        let csv = decision_timeline_csv(path);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("step,node_id,role,proposed,decided"));
+        let rows: Vec<Vec<&str>> = lines.map(|l| l.split(',').collect()).collect();
+        assert_eq!(rows.len(), steps * 3, "one row per step and node");
+        assert!(rows.iter().all(|r| r.len() == 5));
+        assert_eq!(rows[0], vec!["0", "0", "Candidate", "V0", ""]);
+        assert!(rows.iter().any(|r| r[2] == "Decided" && r[4] == "V0"));
+    }
+
+    #[test]
+    fn test_summarize_discovery() {
+        let model = SimConfig::default().build_model();
+        let deliver = |src: usize, dst: usize, msg| ActorModelAction::Deliver {
+            src: Id::from(src),
+            dst: Id::from(dst),
+            msg,
+        };
+        let path = ConsensusPath::from_actions(
+            &model,
+            model.init_states().remove(0),
+            &[
+                deliver(0, 1, ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 }),
+                deliver(1, 0, ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 }),
+            ],
+        )
+        .expect("node 0 gathers a quorum");
+        assert_eq!(
+            summarize_discovery(&path),
+            "2 steps\n  1. 0 -> 1: Propose { value: V0, term: 0, slot: 0 }\n  2. 1 -> 0: Vote { value: V0, term: 0, slot: 0 }\n"
+        );
+    }
+
+    #[test]
+    fn test_value_round_trips_through_strings() {
+        for value in Value::ALL {
+            assert_eq!(value.to_string().parse(), Ok(value));
+            assert_eq!(serde_json::to_string(&value).unwrap(), format!("\"{}\"", value), "logs and JSON agree");
+        }
+        assert_eq!("v0".parse(), Ok(Value::V0));
+        assert_eq!("V2".parse(), Ok(Value::V2));
+        assert_eq!("1".parse(), Ok(Value::V1));
+        assert_eq!("v9".parse::<Value>(), Err("unknown value 'v9', expected v0, v1 or v2".to_string()));
+        assert!(["", "vv0", "+1"].iter().all(|s| s.parse::<Value>().is_err()));
+    }
+
+    #[test]
+    fn test_propose_v2_decides_v2() {
+        assert_eq!("v2".parse(), Ok(Value::V2));
+        assert_eq!("V1".parse(), Ok(Value::V1));
+        assert!("v3".parse::<Value>().is_err());
+
+        let config = SimConfig { proposal: "v2".parse().unwrap(), ..SimConfig::default() };
+        let result = config.build_model().checker().spawn_bfs().join();
+        let path = result.discovery("Progress").expect("a reliable network decides");
+        let decided: BTreeSet<_> = path.last_state().actor_states.iter().filter_map(|s| s.decided_value).collect();
+        assert_eq!(decided, BTreeSet::from([Value::V2]));
+        assert!(result.discovery("Validity").is_none());
+    }
+
+    #[test]
+    fn test_decision_depth() {
+        let model = SimConfig { loss_rate: 0.5, ..SimConfig::default() }.build_model();
+        let propose = ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 };
+        let deliver = |src: usize, dst: usize, msg| ActorModelAction::Deliver {
+            src: Id::from(src),
+            dst: Id::from(dst),
+            msg,
+        };
+        let path = |actions: &[ActorModelAction<ConsensusMsg, Timer>]| {
+            ConsensusPath::from_actions(&model, model.init_states().remove(0), actions).expect("valid path")
+        };
+        let proposed = [
+            ActorModelAction::Drop(Envelope { src: Id::from(0), dst: Id::from(2), msg: propose.clone() }),
+            deliver(0, 1, propose),
         ];
-        assert!(!check_agreement(&bad_states), "Different values should fail agreement");
+        assert_eq!(decision_depth(path(&proposed)), None, "nobody has a quorum yet");
+
+        // The drop isn't a delivery; the Leader decides as the vote lands
+        let mut decided = proposed.to_vec();
+        decided.push(deliver(1, 0, ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 }));
+        assert_eq!(decision_depth(path(&decided)), Some(2));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let graph = StateGraph {
+            nodes: vec!["0: Candidate\n1: Follower".into(), "0: Candidate\n1: Follower \"voted\"".into()],
+            edges: vec![(0, 1, "Propose 0 -> 1".into())],
+            truncated: false,
+        };
+        let dot = to_dot(&graph);
+        assert!(dot.starts_with("digraph states {\n"), "{}", dot);
+        assert!(dot.contains(r#"0 [label="0: Candidate\n1: Follower"];"#), "{}", dot);
+        assert!(dot.contains(r#"1 [label="0: Candidate\n1: Follower \"voted\""];"#), "{}", dot);
+        assert!(dot.contains(r#"0 -> 1 [label="Propose 0 -> 1"];"#), "{}", dot);
+
+        let capped = explore_state_graph(&SimConfig::default().build_model(), 5);
+        assert_eq!(capped.nodes.len(), 5);
+        assert!(capped.truncated);
+        assert!(capped.edges.iter().all(|&(from, to, _)| from < 5 && to < 5));
+        let full = explore_state_graph(&SimConfig::default().build_model(), usize::MAX);
+        assert!(!full.truncated);
+        assert!(full.nodes.iter().any(|label| label.contains("Decided V0")));
+    }
+
+    #[test]
+    fn test_cached_report_skips_the_check() {
+        let dir = std::env::temp_dir().join(format!("consensus-cache-{}", std::process::id()));
+        let config = SimConfig { values: vec![Value::V0], ..SimConfig::default() };
+
+        let fresh = config.cached_check_report(&dir).unwrap();
+        assert!(!fresh.from_cache);
+        let cached = config.cached_check_report(&dir).unwrap();
+        assert!(cached.from_cache, "the second run should read the stored report");
+        assert_eq!((cached.states_explored, &cached.properties), (fresh.states_explored, &fresh.properties));
+        assert_eq!((cached.messages, cached.duration), (fresh.messages, fresh.duration));
+
+        // Another config misses, and so does a file stored under another key
+        let bigger = SimConfig { nodes: 4, ..config.clone() };
+        assert!(load_cached_report(&dir, &bigger).is_none());
+        std::fs::copy(report_cache_path(&dir, &config), report_cache_path(&dir, &bigger)).unwrap();
+        assert!(load_cached_report(&dir, &bigger).is_none(), "stored for a 3-node cluster");
+
+        // A report from another build of the checker isn't trusted either
+        let path = report_cache_path(&dir, &config);
+        let mut stored: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        stored["key"]["crate_version"] = "0.0.0".into();
+        std::fs::write(&path, stored.to_string()).unwrap();
+        assert!(load_cached_report(&dir, &config).is_none(), "written by another version");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_report_json_keys() {
+        let mut report = CheckReport { states_explored: 42, ..CheckReport::default() };
+        report.properties.insert("Agreement", Verdict::Fail);
+        report.properties.insert("Validity", Verdict::Pass);
+        report.properties.insert("Progress", Verdict::Pending);
+        report.counterexamples.insert("Agreement", "2 steps\n".to_string());
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["states_explored"], 42);
+        assert_eq!(json["properties"]["Agreement"], "fail");
+        assert_eq!(json["properties"]["Validity"], "pass");
+        assert_eq!(json["properties"]["Progress"], "pending");
+        assert_eq!(json["counterexamples"]["Agreement"], "2 steps\n");
+        assert_eq!(json["messages"], serde_json::json!({}));
+        assert!(report.failed("Agreement") && !report.failed("Validity"));
+    }
+
+    #[test]
+    fn test_consensus_reached_needs_a_quorum() {
+        let decided = |value: Option<Value>| {
+            std::sync::Arc::new(ConsensusState { decided_value: value, ..ConsensusState::default() })
+        };
+        // One node decided: Progress, but not consensus
+        let lone = [decided(Some(Value::V1)), decided(None), decided(None)];
+        assert!(has_decision(&lone));
+        assert_eq!(consensus_reached(&lone, 2), None);
+
+        let met = [decided(Some(Value::V1)), decided(None), decided(Some(Value::V1))];
+        assert_eq!(consensus_reached(&met, 2), Some(Value::V1));
+        assert_eq!(consensus_reached(&met, 3), None, "two of three is short of a quorum of 3");
+
+        let report = Scenario::new(3).values(&[Value::V0]).build().check_report();
+        assert_eq!(report.consensus, Some(Value::V0));
+    }
+
+    #[test]
+    fn test_decision_histogram() {
+        let decided = |value: Option<Value>| {
+            std::sync::Arc::new(ConsensusState { decided_value: value, ..ConsensusState::default() })
+        };
+        let split = [decided(Some(Value::V0)), decided(Some(Value::V2)), decided(Some(Value::V0)), decided(None)];
+        let histogram = decision_histogram(&split);
+        assert_eq!(histogram, [(Value::V0, 2), (Value::V1, 0), (Value::V2, 1)].into_iter().collect());
+
+        let report = CheckReport { decisions: histogram, ..CheckReport::default() };
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["decisions"], serde_json::json!({"V0": 2, "V1": 0, "V2": 1}));
+        assert_eq!(decision_histogram(&[]).values().sum::<usize>(), 0);
+    }
+
+    #[test]
+    fn test_recovering_node_catches_up_via_state_transfer() {
+        let config = SimConfig {
+            recovering: vec![2],
+            loss_rate: 0.1,
+            ..SimConfig::default()
+        };
+        let model = config.build_model();
+        let result = model.clone().checker().spawn_bfs().join();
+        assert!(result.discovery("Agreement").is_none(), "state transfer broke Agreement");
+
+        // Node 2's Commit is lost; it asks node 1 once node 1 has decided
+        let deliver = |src: usize, dst: usize, msg| ActorModelAction::Deliver {
+            src: Id::from(src),
+            dst: Id::from(dst),
+            msg,
+        };
+        let propose = ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 };
+        let vote = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+        let certificate: BTreeSet<Id> = [Id::from(0), Id::from(1)].into_iter().collect();
+        let commit = |certificate| ConsensusMsg::Commit { value: Value::V0, term: 0, slot: 0, certificate };
+        let path = ConsensusPath::from_actions(
+            &model,
+            model.init_states().remove(0),
+            &[
+                deliver(0, 1, propose.clone()),
+                deliver(0, 2, propose),
+                deliver(1, 0, vote),
+                ActorModelAction::Drop(Envelope {
+                    src: Id::from(0),
+                    dst: Id::from(2),
+                    msg: commit(certificate.clone()),
+                }),
+                deliver(0, 1, commit(certificate.clone())),
+                deliver(2, 1, ConsensusMsg::StateRequest),
+                deliver(
+                    1,
+                    2,
+                    ConsensusMsg::StateResponse {
+                        decided_value: Some(Value::V0),
+                        view: 0,
+                        certificate: certificate.clone(),
+                    },
+                ),
+            ],
+        )
+        .expect("recovery path is enabled");
+        let states = &path.last_state().actor_states;
+        assert_eq!(states[2].decided_value, Some(Value::V0));
+        assert!(check_agreement(states));
+
+        // A response without a quorum behind it is ignored
+        let actor = &config.actors()[2];
+        let mut state = Cow::Owned(ConsensusState::default());
+        let bare = ConsensusMsg::StateResponse {
+            decided_value: Some(Value::V1),
+            view: 0,
+            certificate: [Id::from(1)].into_iter().collect(),
+        };
+        actor.on_msg(Id::from(2), &mut state, Id::from(1), bare, &mut Out::new());
+        assert_eq!(state.decided_value, None);
+
+        // So is a Commit whose certificate is too small or names strangers
+        for forged in [BTreeSet::from([Id::from(1)]), BTreeSet::from([Id::from(1), Id::from(9)])] {
+            actor.on_msg(Id::from(2), &mut state, Id::from(1), commit(forged), &mut Out::new());
+            assert_eq!(state.decided_value, None);
+        }
+        actor.on_msg(Id::from(2), &mut state, Id::from(1), commit(certificate), &mut Out::new());
+        assert_eq!(state.decided_value, Some(Value::V0));
+    }
+
+    #[test]
+    fn test_min_states_guard_catches_stuck_model() {
+        // Nobody proposes, so the cluster sits in its initial state forever
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let stuck = ActorModel::new((), ())
+            .actors((0..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_leader(None)))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .checker()
+            .spawn_bfs()
+            .join()
+            .unique_state_count();
+        assert_eq!(
+            check_min_states(stuck, 10),
+            Err("Suspiciously few states explored (1 < 10) — protocol may be stuck".to_string())
+        );
+
+        let healthy = Scenario::new(3).proposer(0, Value::V0).run_check();
+        assert_eq!(check_min_states(healthy.unique_states, 10), Ok(()));
+    }
+
+    #[test]
+    fn test_explorer_url_follows_path() {
+        let model = SimConfig::default().build_model();
+        let addr: std::net::SocketAddr = "0.0.0.0:3000".parse().unwrap();
+
+        let root = explorer_url(&model, addr, &[]).unwrap();
+        assert!(root.starts_with("http://localhost:3000/#/steps/"), "{}", root);
+        assert_eq!(root.matches('/').count(), 5, "one fingerprint for the initial state");
+
+        // The proposer's broadcast leaves two Propose messages in flight
+        let deeper = explorer_url(&model, addr, &[1, 0]).unwrap();
+        assert!(deeper.starts_with(&root));
+        assert_eq!(deeper.matches('/').count(), 7);
+
+        let err = explorer_url(&model, addr, &[5]).unwrap_err();
+        assert_eq!(err, "step 0: transition 5 requested but only 2 available");
+    }
+
+    #[test]
+    fn test_validate_explorer_address() {
+        assert!(validate_explorer_address("127.0.0.1:0").is_ok());
+        assert!(validate_explorer_address("not an address").is_err());
+
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = taken.local_addr().unwrap().to_string();
+        assert!(validate_explorer_address(&addr).unwrap_err().starts_with("cannot bind"));
+    }
+
+    #[test]
+    fn test_crashed_node_never_votes() {
+        let scenario = Scenario::new(3).proposer(0, Value::V0).crash(2);
+        assert_eq!(crashed_ids(&scenario.config().build_model()), vec![Id::from(2)]);
+
+        let summary = scenario.run_check();
+        assert!(summary.violations.is_empty(), "{:?}", summary.violations);
+
+        // Exhaustively confirm node 2 is absent from every vote set
+        let result = scenario.config().build_model().checker().spawn_bfs().join();
+        assert!(result.discovery("CrashedNeverVote").is_none());
+
+        let forged = vec![std::sync::Arc::new(ConsensusState {
+            role: NodeRole::Candidate,
+            votes_received: HashMap::from([(Value::V0, [Id::from(1), Id::from(2)].into_iter().collect())]),
+            ..ConsensusState::default()
+        })];
+        assert!(!check_crashed_never_vote(&forged, &[Id::from(2)]));
+        assert!(check_crashed_never_vote(&forged, &[]));
+        assert!(!SafetyProperty::CrashedNeverVote.eval(&scenario.config().model_config(), &forged));
+        assert_eq!(scenario.config().check_report().properties["CrashedNeverVote"], Verdict::Pass);
+    }
+
+    #[test]
+    fn test_value_domain_shrinks_state_space() {
+        let two = SimConfig {
+            values: Value::domain(2),
+            ..SimConfig::default()
+        };
+        let three = SimConfig {
+            values: Value::domain(3),
+            ..SimConfig::default()
+        };
+
+        // V2 is never even proposed, and Validity pins decisions to the domain
+        for scenario in two.contention_scenarios() {
+            assert!(!proposed_values(&scenario.build_model()).contains(&Value::V2));
+        }
+        let outside = vec![std::sync::Arc::new(ConsensusState {
+            decided_value: Some(Value::V2),
+            ..ConsensusState::default()
+        })];
+        assert!(!SafetyProperty::Validity.eval(&two.model_config(), &outside));
+        assert!(SafetyProperty::Validity.eval(&three.model_config(), &outside));
+        let small = two.run_contention_check();
+        let large = three.run_contention_check();
+        assert!(small.violations.is_empty(), "{:?}", small.violations);
+        assert!(
+            small.unique_states < large.unique_states,
+            "{} states with 2 values, {} with 3",
+            small.unique_states,
+            large.unique_states
+        );
+    }
+
+    #[test]
+    fn test_wasted_messages_on_decision_path() {
+        let summary = Scenario::new(3).proposer(0, Value::V0).run_check();
+        assert!(summary.progress);
+        let wasted = summary.wasted_messages.expect("decision path is measured");
+        // The shortest path to a decision has nothing to spare
+        assert_eq!(wasted, 0);
+
+        // The checker prunes deliveries that neither change state nor send
+        // anything, so only ones that merely trigger sends show up: a
+        // Byzantine follower answers a proposal without recording it
+        let config = SimConfig {
+            proposer: 2,
+            byzantine_faults: 1,
+            ..SimConfig::default()
+        };
+        let model = config.build_model();
+        let init = model.init_states().remove(0);
+        let path = ConsensusPath::from_actions(
+            &model,
+            init,
+            &[ActorModelAction::Deliver {
+                src: Id::from(2),
+                dst: Id::from(0),
+                msg: ConsensusMsg::Propose { value: Value::V0, term: 0, slot: 0 },
+            }],
+        )
+        .expect("proposal is in flight");
+        assert_eq!(wasted_deliveries(path), 1);
+    }
+
+    #[test]
+    fn test_min_faulty_to_break_agreement() {
+        // ⌈n/2⌉ for a simple majority: that many faulty leaves the rest short
+        for (n, faulty) in [(3, 2), (4, 2), (5, 3), (7, 4)] {
+            assert_eq!(min_faulty_to_break_agreement(n, &SimpleMajority), faulty, "n = {}", n);
+        }
+        assert_eq!(min_faulty_to_break_agreement(4, &ByzantineTwoThirds), 2);
+        assert_eq!(min_faulty_to_break_agreement(7, &ByzantineTwoThirds), 3);
+        assert_eq!(min_faulty_to_break_agreement(4, &Threshold(2)), 3);
+        assert_eq!(min_faulty_to_break_agreement(3, &Threshold(4)), 0, "never an honest quorum");
+        assert_eq!(SimConfig::default().check_report().safety_margin, 2);
+
+        // Stake and views count: losing the heavy node alone leaves 2 of 4
+        // stake, short of a majority, and a view of 2 needs both
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let weighted = ConsensusActor::new(peer_ids.clone()).with_weights(vec![2, 1, 1]);
+        assert_eq!(weighted.min_faulty_to_break_agreement(), 1);
+        assert_eq!(ConsensusActor::new(peer_ids).min_faulty_to_break_agreement(), 2);
+        let partial = SimConfig { views: BTreeMap::from([(0, vec![0, 1])]), ..SimConfig::default() };
+        assert_eq!(partial.safety_margin(), 1);
+    }
+
+    #[test]
+    fn test_min_safe_quorum_matches_byzantine_bound() {
+        assert_eq!(byzantine_quorum_bound(4, 1), 3);
+        assert_eq!(byzantine_quorum_bound(7, 2), 5);
+        assert_eq!(min_safe_quorum(7, 2), Some(5));
+    }
+
+    #[test]
+    fn test_total_votes_bound() {
+        let with_votes = |ids: &[usize]| {
+            std::sync::Arc::new(ConsensusState {
+                role: NodeRole::Candidate,
+                proposed_value: Some(Value::V0),
+                votes_received: HashMap::from([(Value::V0, ids.iter().copied().map(Id::from).collect())]),
+                decided_value: None,
+                ..ConsensusState::default()
+            })
+        };
+
+        // 2 nodes, each holding both votes: 4 == 2 * 2, still within bound
+        let states = vec![with_votes(&[0, 1]), with_votes(&[0, 1])];
+        assert!(check_total_votes_bound(&states, 2), "n * n votes should be allowed");
+
+        // Fabricated state with more votes than a 2-node cluster could ever produce
+        let bad_states = vec![with_votes(&[0, 1, 2]), with_votes(&[0, 1])];
+        assert!(!check_total_votes_bound(&bad_states, 2), "5 votes exceeds 2 * 2 bound");
     }
 }
@@ -1,133 +1,1006 @@
 // Model checker CLI for the consensus protocol
 // Run with: cargo run --release -- check
 // Or explore with: cargo run --release -- explore
+// Validate an experiment file with: cargo run --release -- validate experiment.json
+// Find the smallest safe quorum with: cargo run --release -- min-quorum --nodes 7 --byzantine 2
+// Print one seeded random run with: cargo run --release -- simulate --seed 7
+// Re-drive an exported trace with: cargo run --release -- replay cex.json
+// Compare network models with: cargo run --release -- matrix
+// Scale across cluster sizes with: cargo run --release -- sweep --min 3 --max 7 --out results.csv
 // 
-// TODO: add more CLI args for node count, message loss rate, etc
-// FIXME: explore mode isn't working yet (port binding issues?)
+// TODO: add more CLI args for partitions, crashes, etc
 
 use consensus_stateright::*;
-use stateright::actor::{ActorModel, Id, Network};
-use stateright::{Checker, Expectation, Model};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
     
     if args.len() < 2 {
-        println!("Usage: {} <check|explore> [options]", args[0]);
+        println!("Usage: {} <check|explore|simulate> [options]", args[0]);
         println!("\nExamples:");
         println!("  {} check           - Run model checker", args[0]);
+        println!("  {} check --emit-repro repro.rs - Write a regression test for any violation", args[0]);
+        println!("  {} check --trace-out cex.json  - Write any counterexample's states and actions as JSON", args[0]);
+        println!("  {} check --loss-rate 0.1           - Let the network drop messages", args[0]);
+        println!("  {} check --max-dupes 2         - Allow each message to be delivered up to twice", args[0]);
+        println!("  {} check --network ordered     - FIFO channels (also unordered, duplicating)", args[0]);
+        println!("  {} check --values 2            - Only propose V0 and V1", args[0]);
+        println!("  {} check --propose v2          - Node 0 only proposes V2 (rivals still vary with --contend)", args[0]);
+        println!("  {} check --contend             - Pit node 1 against node 0 with every other value", args[0]);
+        println!("  {} check --all-propose         - Every node campaigns for its own value at once", args[0]);
+        println!("  {} check --protocol paxos      - Check single-decree Paxos instead", args[0]);
+        println!("  {} check --fair                - Also check that every Leader leads to a decision, and judge", args[0]);
+        println!("                                  liveness only on runs that eventually deliver every message");
+        println!("  {} check --check-liveness      - Also check that every run ends with all live nodes decided", args[0]);
+        println!("  {} check --adversary           - Let the network withhold any one message forever (FLP);", args[0]);
+        println!("                                  checks Termination and prints a run that never ends");
+        println!("  {} check --timeline out.csv    - Write per-node roles along the shortest decision path", args[0]);
+        println!("  {} check --export-dot graph.dot - Write the explored state graph for GraphViz", args[0]);
+        println!("  {} check --dot-max-nodes 200   - Stop the graph at 200 states (default 1000)", args[0]);
+        println!("  {} check --min-states 50       - Fail if exploration looks stuck", args[0]);
+        println!("  {} check --search dfs --max-depth 8 - Depth-first, at most 8 steps deep", args[0]);
+        println!("  {} check --nodes 7 --max-states 100000 - Give up on each scenario after 100000 states", args[0]);
+        println!("  {} check --threads 8           - Search with 8 threads (default {})", args[0], DEFAULT_THREADS);
+        println!("  {} check --symmetry --nodes 5  - Depth-first, exploring id-renamed states once", args[0]);
+        println!("  {} check --format json         - Print the results as JSON for CI", args[0]);
+        println!("  {} check --cache .check-cache  - Reuse the report of an identical earlier run", args[0]);
+        println!("  {} check --quiet               - Skip the banners; exits 0 if safe, 1 on a violation,", args[0]);
+        println!("                                  2 if misconfigured");
+        println!("  {} check --nodes 5             - Model a 5-node cluster (also works for explore)", args[0]);
         println!("  {} explore         - Launch web UI (port 3000)", args[0]);
+        println!("  {} explore --path 1,0      - Open the UI on the state reached by those transitions", args[0]);
+        println!("  {} explore --network ordered - Show the model check would search with the same flags", args[0]);
+        println!("  {} simulate --seed 7      - Print one random run, the same for the same seed", args[0]);
+        println!("  {} simulate --max-steps 20 - Stop the run after at most 20 steps (default 100)", args[0]);
+        println!("  {} simulate --verbose     - Also log each node's decisions (RUST_LOG=trace adds ignored messages)", args[0]);
+        println!("  {} simulate --drop-vote-prob 0.3 - Each node fails to send a Vote 30% of the time", args[0]);
+        println!("  {} simulate --schedule propose-first - Deliver Proposes first (also random, commit-last)", args[0]);
+        println!("  {} matrix          - Check ordered, unordered and duplicating networks side by side", args[0]);
+        println!("  {} matrix --nodes 4 - The same for a 4-node cluster", args[0]);
+        println!("  {} sweep --min 3 --max 7 --out results.csv - Check each cluster size, one CSV row per size", args[0]);
+        println!("  {} sweep --max 5 --max-states 100000 - Print the CSV, capping each search", args[0]);
+        println!("  {} replay cex.json - Re-drive an exported trace, showing every step", args[0]);
+        println!("  {} replay cex.json --config experiment.json - Replay against that config's cluster", args[0]);
+        println!("  {} validate <file> - Check a JSON experiment config", args[0]);
+        println!("  {} min-quorum --nodes 7 --byzantine 2 - Smallest quorum that keeps Agreement", args[0]);
         return Ok(());
     }
 
     let command = &args[1];
     
     match command.as_str() {
-        "check" => run_checker(),
-        "explore" => run_explorer(),
+        "check" => {
+            // Scripts tell a bad invocation from a violation by the exit code
+            let misconfigured = |e: Box<dyn std::error::Error>| -> ! {
+                eprintln!("Error: {}", e);
+                std::process::exit(EXIT_MISCONFIGURED);
+            };
+            let options = CheckOptions::parse(&args).unwrap_or_else(|e| misconfigured(e));
+            let config = check_config(&options).unwrap_or_else(|e| misconfigured(e.into()));
+            let code = match options.protocol {
+                Protocol::Pbft => run_checker(options, config).unwrap_or_else(|e| misconfigured(e)),
+                Protocol::Paxos => run_paxos_checker(options, config).unwrap_or_else(|e| misconfigured(e)),
+            };
+            std::process::exit(code);
+        }
+        "explore" => {
+            let path = match flag_value(&args, "--path") {
+                Some(p) => parse_path(&p)?,
+                None => Vec::new(),
+            };
+            // The same model flags as `check`, so the UI shows what it searches
+            let options = CheckOptions::parse(&args)?;
+            let config = check_config(&options)?;
+            if let Err(e) = run_explorer(&path, &options.model, &config) {
+                println!("Explorer failed to start: {}", e);
+                std::process::exit(1);
+            }
+        }
+        "simulate" => {
+            let seed = flag_value(&args, "--seed")
+                .map(|s| s.parse().map_err(|_| format!("--seed expects a number, got '{}'", s)))
+                .transpose()?
+                .unwrap_or(0);
+            let max_steps = parse_count(&args, "--max-steps", 100)?;
+            let nodes = parse_nodes(&args)?;
+            let drop_vote_prob = match flag_value(&args, "--drop-vote-prob") {
+                Some(p) => p.parse().map_err(|_| format!("--drop-vote-prob expects a number, got '{}'", p))?,
+                None => 0.0,
+            };
+            if !(0.0..=1.0).contains(&drop_vote_prob) {
+                return Err(format!("--drop-vote-prob must be between 0 and 1, got {}", drop_vote_prob).into());
+            }
+            let schedule = flag_value(&args, "--schedule").map(|s| s.parse()).transpose()?.unwrap_or_default();
+            if args.iter().any(|a| a == "--verbose") {
+                // Only here: the checker would log every state it explores
+                env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug")).init();
+            }
+            run_simulation(nodes, seed, max_steps, drop_vote_prob, schedule);
+        }
+        "matrix" => run_matrix(parse_nodes(&args)?),
+        "sweep" => {
+            let min = parse_count(&args, "--min", 3)?;
+            let max = parse_count(&args, "--max", min)?;
+            if min == 0 || max < min {
+                return Err(format!("sweep needs 1 <= --min <= --max, got {}..{}", min, max).into());
+            }
+            run_sweep(min..=max, parse_max_states(&args)?, flag_value(&args, "--out").as_deref())?;
+        }
+        "replay" => match args.get(2).filter(|a| !a.starts_with("--")) {
+            Some(path) => run_replay(path, flag_value(&args, "--config").as_deref())?,
+            None => {
+                println!("Usage: {} replay <trace.json> [--config <config.json>]", args[0]);
+                std::process::exit(1);
+            }
+        },
+        "validate" => match args.get(2) {
+            Some(path) => {
+                if !run_validate(path)? {
+                    std::process::exit(1);
+                }
+            }
+            None => {
+                println!("Usage: {} validate <config.json>", args[0]);
+                std::process::exit(1);
+            }
+        },
+        "min-quorum" => {
+            let nodes = parse_count(&args, "--nodes", 4)?;
+            let byzantine = parse_count(&args, "--byzantine", 1)?;
+            run_min_quorum(nodes, byzantine)?;
+        }
         _ => {
             println!("Unknown command: {}", command);
-            println!("Use 'check' or 'explore'");
+            println!("Use 'check', 'explore', 'simulate', 'matrix', 'sweep', 'replay', 'validate' or 'min-quorum'");
+        }
+    }
+
+    Ok(())
+}
+
+/// Looks up the value following `flag`, e.g. `--emit-repro out.rs`
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+/// Which protocol `check` explores
+enum Protocol {
+    Pbft,
+    Paxos,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Flags accepted by `check`
+struct CheckOptions {
+    protocol: Protocol,
+    /// Everything that shapes the models, shared with `explore`
+    model: ModelOptions,
+    emit_repro: Option<String>,
+    /// JSON file for the counterexample's full path
+    trace_out: Option<String>,
+    /// CSV file for the per-node decision timeline
+    timeline: Option<String>,
+    /// DOT file for the state graph of the first scenario
+    export_dot: Option<String>,
+    /// Most states to put in the DOT graph
+    dot_max_nodes: usize,
+    /// Fail the run if fewer unique states than this were explored
+    min_states: Option<usize>,
+    search: Search,
+    /// Collapse states that only differ by node ids; implies depth-first
+    symmetry: bool,
+    max_depth: Option<usize>,
+    /// Stop each search after this many unique states, marking the run incomplete
+    max_states: Option<usize>,
+    threads: usize,
+    /// Human-readable text, or a `CheckReport` as JSON for CI
+    format: Format,
+    /// Leave out the banners and progress lines, keeping the results
+    quiet: bool,
+    /// Directory of stored reports, one per run description
+    cache: Option<String>,
+}
+
+impl CheckOptions {
+    fn parse(args: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        let max_dupes = match flag_value(args, "--max-dupes") {
+            Some(k) => Some(k.parse().map_err(|_| format!("--max-dupes expects a number, got '{}'", k))?),
+            None => None,
+        };
+        let network = match flag_value(args, "--network") {
+            Some(kind) => kind.parse::<NetworkKind>().map_err(|e| format!("--network: {}", e))?,
+            // --max-dupes on its own still means a duplicating network
+            None if max_dupes.is_some() => NetworkKind::Duplicating,
+            None => NetworkKind::Unordered,
+        };
+        if max_dupes.is_some() && network != NetworkKind::Duplicating {
+            return Err("--max-dupes needs --network duplicating".into());
+        }
+        let loss_rate = match flag_value(args, "--loss-rate") {
+            Some(r) => r.parse().map_err(|_| format!("--loss-rate expects a number, got '{}'", r))?,
+            None => 0.0,
+        };
+        if !(0.0..=1.0).contains(&loss_rate) {
+            return Err(format!("--loss-rate must be between 0 and 1, got {}", loss_rate).into());
+        }
+        let values = parse_count(args, "--values", Value::ALL.len())?;
+        if values == 0 || values > Value::ALL.len() {
+            return Err(format!("--values must be between 1 and {}, got {}", Value::ALL.len(), values).into());
+        }
+        let propose = match flag_value(args, "--propose") {
+            Some(v) => Some(v.parse::<Value>().map_err(|e| format!("--propose: {}", e))?),
+            None => None,
+        };
+        if let Some(value) = propose.filter(|v| !Value::domain(values).contains(v)) {
+            return Err(format!("--propose {} is not in the value domain {:?}", value, Value::domain(values)).into());
+        }
+        let all_propose = args.iter().any(|a| a == "--all-propose");
+        if all_propose && propose.is_some() {
+            return Err("--all-propose already picks every node's value, drop --propose".into());
+        }
+        let protocol = match flag_value(args, "--protocol").as_deref() {
+            None | Some("pbft") => Protocol::Pbft,
+            Some("paxos") => Protocol::Paxos,
+            Some(other) => return Err(format!("--protocol expects pbft or paxos, got '{}'", other).into()),
+        };
+        let adversary = args.iter().any(|a| a == "--adversary");
+        if adversary && matches!(protocol, Protocol::Paxos) {
+            return Err("--adversary only works with the default protocol".into());
+        }
+        let symmetry = args.iter().any(|a| a == "--symmetry");
+        let search = match flag_value(args, "--search").as_deref() {
+            None if symmetry => Search::Dfs,
+            None | Some("bfs") => Search::Bfs,
+            Some("dfs") => Search::Dfs,
+            Some(other) => return Err(format!("--search expects bfs or dfs, got '{}'", other).into()),
+        };
+        if symmetry && (search != Search::Dfs || matches!(protocol, Protocol::Paxos)) {
+            return Err("--symmetry only works with the default protocol and --search dfs".into());
+        }
+        let format = match flag_value(args, "--format").as_deref() {
+            None | Some("text") => Format::Text,
+            Some("json") => Format::Json,
+            Some(other) => return Err(format!("--format expects text or json, got '{}'", other).into()),
+        };
+        Ok(CheckOptions {
+            protocol,
+            model: ModelOptions {
+                nodes: parse_nodes(args)?,
+                network,
+                max_dupes,
+                loss_rate,
+                values,
+                propose,
+                contention: args.iter().any(|a| a == "--contend"),
+                all_propose,
+                fair: args.iter().any(|a| a == "--fair"),
+                // The adversary is only worth having with Termination checked
+                check_liveness: adversary || args.iter().any(|a| a == "--check-liveness"),
+                adversary,
+            },
+            emit_repro: flag_value(args, "--emit-repro"),
+            trace_out: flag_value(args, "--trace-out"),
+            timeline: flag_value(args, "--timeline"),
+            export_dot: flag_value(args, "--export-dot"),
+            dot_max_nodes: parse_count(args, "--dot-max-nodes", 1000)?,
+            min_states: match flag_value(args, "--min-states") {
+                Some(_) => Some(parse_count(args, "--min-states", 0)?),
+                None => None,
+            },
+            search,
+            symmetry,
+            max_depth: match flag_value(args, "--max-depth") {
+                Some(_) => Some(parse_count(args, "--max-depth", 0)?),
+                None => None,
+            },
+            max_states: parse_max_states(args)?,
+            threads: match parse_count(args, "--threads", DEFAULT_THREADS)? {
+                0 => return Err("--threads must be at least 1".into()),
+                threads => threads,
+            },
+            format,
+            quiet: args.iter().any(|a| a == "--quiet"),
+            cache: flag_value(args, "--cache"),
+        })
+    }
+}
+
+fn print_proposal(options: &CheckOptions) {
+    match options.model.propose {
+        _ if options.model.all_propose => println!("Proposal: every node proposes its own value at once"),
+        Some(value) if options.model.contention => println!("Proposal: node 0 proposes {} against a rival", value),
+        Some(value) => println!("Proposal: node 0 proposes {}", value),
+        None if options.model.contention => println!("Proposal: node 0 proposes each value in turn against a rival"),
+        None => println!("Proposal: node 0 proposes each value in turn"),
+    }
+}
+
+fn print_network(options: &CheckOptions) {
+    match (options.model.network, options.model.config().max_dupes) {
+        (NetworkKind::Ordered, _) => println!("Network: Ordered (FIFO between each pair of nodes), non-duplicating"),
+        // Paxos has no duplication support
+        (NetworkKind::Duplicating, Some(k)) if matches!(options.protocol, Protocol::Pbft) => {
+            println!("Network: Unordered, duplicating (each message at most {} times)", k)
+        }
+        _ => println!("Network: Unordered, non-duplicating"),
+    }
+}
+
+fn print_loss(options: &CheckOptions) {
+    if options.model.loss_rate > 0.0 {
+        // The checker tries every drop, so the rate itself doesn't matter
+        println!("Loss: any message may be dropped (rate {})", options.model.loss_rate);
+        if options.model.fair {
+            println!("  Fair: liveness is only checked on schedules that eventually deliver every message");
+        } else if options.model.check_liveness {
+            println!("  Termination can legitimately fail: a lost message may strand a run (FLP)");
+        }
+    } else if options.model.adversary {
+        println!("Loss: an adversary may withhold any one message forever");
+        if options.model.fair {
+            println!("  Fair: liveness is only checked on schedules that eventually deliver every message");
+        } else {
+            println!("  Termination is expected to fail: one delayed message is enough to strand a run (FLP)");
+        }
+    } else {
+        println!("Loss: none");
+    }
+}
+
+fn print_search(options: &CheckOptions) {
+    let symmetry = if options.symmetry { " with symmetry reduction" } else { "" };
+    let cap = options.max_states.map(|max| format!(", at most {} states per search", max)).unwrap_or_default();
+    match options.max_depth {
+        Some(depth) => println!("Search: {}{}, depth <= {}{}", options.search.name(), symmetry, depth, cap),
+        None if cap.is_empty() => println!("Search: {}{}, unbounded", options.search.name(), symmetry),
+        None => println!("Search: {}{}{}", options.search.name(), symmetry, cap),
+    }
+    println!("Threads: {}", options.threads);
+}
+
+/// Reads a numeric flag, falling back to `default` when it's absent
+fn parse_count(args: &[String], flag: &str, default: usize) -> Result<usize, String> {
+    match flag_value(args, flag) {
+        Some(n) => n.parse().map_err(|_| format!("{} expects a number, got '{}'", flag, n)),
+        None => Ok(default),
+    }
+}
+
+/// `--nodes N` for `check` and `explore`, at least one node, default 3
+fn parse_nodes(args: &[String]) -> Result<usize, String> {
+    let nodes = parse_count(args, "--nodes", 3)?;
+    if nodes == 0 {
+        return Err("--nodes must be at least 1".to_string());
+    }
+    Ok(nodes)
+}
+
+/// `--max-states N` for `check` and `sweep`: no cap when absent, and a cap
+/// of 0 would check nothing
+fn parse_max_states(args: &[String]) -> Result<Option<usize>, String> {
+    match flag_value(args, "--max-states") {
+        Some(_) => match parse_count(args, "--max-states", 0)? {
+            0 => Err("--max-states must be at least 1".to_string()),
+            max => Ok(Some(max)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Parses an explorer path like `1,0,2` (transition indices, see `explorer_url`)
+fn parse_path(path: &str) -> Result<Vec<usize>, String> {
+    path.split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.trim().parse().map_err(|_| format!("--path expects comma-separated indices, got '{}'", s)))
+        .collect()
+}
+
+/// Replays a trace file, either bare deliveries or a `--trace-out`
+/// counterexample, printing what each delivery changed and where every node
+/// ended up. Without `config_path` the default cluster is assumed.
+fn run_replay(path: &str, config_path: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(path)?;
+    let trace: Vec<DeliveryRecord> = match serde_json::from_str(&json) {
+        Ok(trace) => trace,
+        Err(_) => deliveries_from_trace(&serde_json::from_str::<Vec<TraceStep>>(&json)?),
+    };
+    let config = match config_path {
+        Some(config_path) => SimConfig::from_json(&std::fs::read_to_string(config_path)?)?,
+        None => SimConfig::for_trace(&trace),
+    };
+    config
+        .validate()
+        .map_err(|problems| format!("invalid configuration: {}", problems.join("; ")))?;
+    if let Some(d) = trace.iter().find(|d| usize::from(d.src.max(d.dst)) >= config.nodes) {
+        return Err(format!("trace delivery {:?} is outside the {}-node cluster", d, config.nodes).into());
+    }
+
+    println!("=== Replaying {} ===", path);
+    println!("Nodes: {}, deliveries: {}\n", config.nodes, trace.len());
+    print!("{}", render_trace(&config, &trace));
+    println!("\nFinal states:");
+    for (node, state) in replay(&config, &trace).iter().enumerate() {
+        println!(
+            "  node {}: {:?}, term {}, proposed {:?}, decided {:?}",
+            node, state.role, state.term, state.proposed_value, state.decided_value
+        );
+    }
+    Ok(())
+}
+
+/// Pre-flight check for batch runs: parse the config and report every problem
+/// without exploring any states. Returns whether the config is valid.
+fn run_validate(path: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(path)?;
+    let config = match SimConfig::from_json(&json) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Invalid config {}: {}", path, e);
+            return Ok(false);
+        }
+    };
+
+    match config.validate() {
+        Ok(()) => {
+            println!("Config valid");
+            Ok(true)
+        }
+        Err(problems) => {
+            println!("Config invalid ({} problems):", problems.len());
+            for problem in problems {
+                println!("  - {}", problem);
+            }
+            Ok(false)
         }
     }
+}
+
+/// Sweeps quorum sizes against a Byzantine split and compares the smallest
+/// safe one with the textbook bound
+fn run_min_quorum(nodes: usize, byzantine: usize) -> Result<(), String> {
+    if nodes < 2 || byzantine >= nodes {
+        return Err(format!(
+            "need at least 2 nodes and fewer Byzantine faults than nodes (got {} and {})",
+            nodes, byzantine
+        ));
+    }
 
+    println!("=== Minimal Safe Quorum ===");
+    println!("Nodes: {}", nodes);
+    println!("Byzantine: {}", byzantine);
+    println!();
+
+    let theory = byzantine_quorum_bound(nodes, byzantine);
+    match min_safe_quorum(nodes, byzantine) {
+        Some(quorum) => {
+            println!("Smallest quorum preserving Agreement: {}", quorum);
+            println!("Theory (q > (n + f) / 2, i.e. 2f+1 when n = 3f+1): {}", theory);
+            if quorum == theory {
+                println!("[MATCH] Checker agrees with theory");
+            } else {
+                println!("[DIFF] Checker found {}, theory says {}", quorum, theory);
+            }
+        }
+        None => println!("No quorum size preserves Agreement (theory says {})", theory),
+    }
     Ok(())
 }
 
-fn run_checker() {
-    println!("=== Consensus Protocol Model Checker ===");
-    println!("Nodes: 3");
-    println!("Values: 2");
-    println!("Network: Unordered, non-duplicating");
+fn run_matrix(nodes: usize) {
+    println!("=== Network Matrix ===");
+    println!("Nodes: {}", nodes);
     println!();
 
-    let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
-    
-    let model = ActorModel::new((), ())
-        .actor(ConsensusActor::new(peer_ids.clone()))
-        .actor(ConsensusActor::new(peer_ids.clone()))
-        .actor(ConsensusActor::new(peer_ids.clone()))
-        .init_network(Network::new_unordered_nonduplicating([]))
-        .property(
-            Expectation::Always,
-            "Agreement",
-            |_, state| check_agreement(&state.actor_states)
-        )
-        .property(
-            Expectation::Always,
-            "Validity",
-            |_, state| check_validity(&state.actor_states)
-        )
-        .property(
-            Expectation::Sometimes,
-            "Progress",
-            |_, state| has_decision(&state.actor_states)
+    let config = SimConfig { nodes, ..SimConfig::default() };
+    let verdict = |report: &CheckReport, name| match report.properties.get(name) {
+        Some(Verdict::Pass) => "PASS",
+        Some(Verdict::Fail) => "FAIL",
+        _ => "PENDING",
+    };
+    println!("{:<12} {:>15} {:<10} {:<10} Progress", "Network", "States", "Agreement", "Validity");
+    for (kind, report) in network_matrix(&config) {
+        println!(
+            "{:<12} {:>15} {:<10} {:<10} {}",
+            kind.name(),
+            report.states_explored,
+            verdict(&report, "Agreement"),
+            verdict(&report, "Validity"),
+            verdict(&report, "Progress"),
         );
+    }
+}
 
-    println!("Starting model checker...");
-    
-    // Using 4 threads for checking. on my laptop this seems optimal
-    // tried 8 but didn't help much, probably memory bound not CPU bound
-    let checker = model.checker().threads(4);
-    
-    println!("Running breadth-first search...");
-    let result = checker.spawn_bfs().join();
+/// Checks each cluster size in `nodes` and writes a CSV row per size as it
+/// finishes. With `out`, rows are appended to that file and the header is
+/// only written if the file is new or empty; otherwise the CSV goes to stdout.
+fn run_sweep(
+    nodes: std::ops::RangeInclusive<usize>,
+    max_states: Option<usize>,
+    out: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
 
-    println!("\n=== Results ===");
-    println!("States explored: {}", result.unique_state_count());
-    
-    // Check for discoveries
-    if let Some(_discovery) = result.discovery("Agreement") {
-        println!("\n[FAIL] Agreement property violated!");
+    let mut sink: Box<dyn Write> = match out {
+        Some(path) => {
+            let file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            if file.metadata()?.len() == 0 {
+                (&file).write_all(sweep_csv_header().as_bytes())?;
+            }
+            Box::new(file)
+        }
+        None => {
+            print!("{}", sweep_csv_header());
+            Box::new(std::io::stdout())
+        }
+    };
+    let config = SimConfig { max_states, ..SimConfig::default() };
+    for (sized, report) in size_sweep(&config, nodes) {
+        sink.write_all(sweep_csv_row(&sized, &report).as_bytes())?;
+        sink.flush()?;
+        if let Some(path) = out {
+            println!("{} nodes: {} states in {:.2?}, appended to {}", sized.nodes, report.states_explored, report.duration, path);
+        }
+    }
+    Ok(())
+}
+
+/// The base config `check` builds its scenarios from, refusing to start on
+/// one that can't be checked meaningfully, e.g. with disjoint quorums or a
+/// quorum no cluster of that size can reach
+fn check_config(options: &CheckOptions) -> Result<SimConfig, String> {
+    let config = SimConfig {
+        search: options.search,
+        max_depth: options.max_depth,
+        max_states: options.max_states,
+        ..options.model.config()
+    };
+    config
+        .try_build_model()
+        .map_err(|problems| format!("invalid configuration: {}", problems.join("; ")))?;
+    // Renaming ids would merge states that behave differently
+    if options.symmetry && !config.is_symmetric() {
+        return Err("--symmetry needs interchangeable nodes: no crashes, recovery, Byzantine nodes, partitions or views".into());
+    }
+    Ok(config)
+}
+
+/// Checks every scenario of `config` and prints the results.
+/// Returns the exit code, as [`CheckReport::exit_code`] or 1 for too few
+/// states; an error means an output file couldn't be written.
+fn run_checker(options: CheckOptions, config: SimConfig) -> Result<i32, Box<dyn std::error::Error>> {
+    let scenarios = options.model.scenarios(&config);
+    let text = options.format == Format::Text;
+    let banners = text && !options.quiet;
+
+    // Everything that changes the report; threads only change how fast it comes
+    let cache_key = serde_json::json!({
+        "config": config,
+        "propose": options.model.propose,
+        "fair": options.model.fair,
+        "check_liveness": options.model.check_liveness,
+        "symmetry": options.symmetry,
+    });
+    // A cached report has no paths to write out, so those runs check afresh
+    let writes_paths = options.trace_out.is_some()
+        || options.timeline.is_some()
+        || options.export_dot.is_some()
+        || options.emit_repro.is_some();
+    if writes_paths && options.cache.is_some() && text {
+        println!("Not using --cache: the requested output files need a fresh check");
+    }
+    let cache = options.cache.as_deref().filter(|_| !writes_paths).map(std::path::Path::new);
+    if let Some(report) = cache.and_then(|dir| load_cached_report(dir, &cache_key)) {
+        if text {
+            let path = report_cache_path(cache.expect("loaded from it"), &cache_key);
+            println!("Report loaded from cache ({}), nothing re-checked", path.display());
+        }
+        if !emit_report(&report, &options)? {
+            return Ok(EXIT_VIOLATION);
+        }
+        return Ok(report.exit_code());
+    }
+
+    if banners {
+        println!("=== Consensus Protocol Model Checker ===");
+        println!("Nodes: {}", config.nodes);
+        println!("Values: {:?} ({} scenarios)", config.values, scenarios.len());
+        print_proposal(&options);
+        print_network(&options);
+        print_loss(&options);
+        print_search(&options);
+        println!();
+
+        println!("Starting model checker...");
+    }
+
+    let mut tally = CheckTally::new(&config);
+    let mut stranded_leader = false;
+    let mut stuck_run = false;
+    let mut first_violation = None;
+    let mut first_decision = None;
+    let mut stuck_path = None;
+    let check = |model: ConsensusModel| {
+        if options.symmetry {
+            run_symmetric_search(model, config.max_depth, config.max_states, options.threads)
+        } else {
+            run_search(model, config.search, config.max_depth, config.max_states, options.threads)
+        }
+    };
+    for scenario in &scenarios {
+        if banners {
+            match scenario.rival {
+                _ if scenario.all_propose => println!("Running {} search (every node proposing)...", scenario.search.name()),
+                Some((_, rival)) => println!("Running {} search ({} vs {})...", scenario.search.name(), scenario.proposal, rival),
+                None => println!("Running {} search ({} uncontested)...", scenario.search.name(), scenario.proposal),
+            }
+        }
+        // Under --fair, liveness only has to hold on the schedules that
+        // deliver everything, while safety still faces every drop
+        let (model, liveness) = options.model.scenario_models(scenario);
+        let (result, liveness) = (check(model), liveness.map(check));
+        tally.add(scenario, &*result);
+        if let Some(liveness) = &liveness {
+            tally.count(&**liveness);
+        }
+        if first_decision.is_none() {
+            first_decision = result.discovery("Progress");
+        }
+        let liveness = liveness.as_ref().unwrap_or(&result);
+        stranded_leader |= liveness.discovery("LeaderLeadsToDecision").is_some();
+        if liveness.discovery("Termination").is_some() {
+            stuck_run = true;
+            if stuck_path.is_none() {
+                let live = if options.model.fair { scenario.fair_schedules() } else { scenario.clone() };
+                stuck_path = find_stuck_run(live.build_model());
+            }
+        }
+        if first_violation.is_none() {
+            first_violation = SafetyProperty::ALL
+                .into_iter()
+                .find_map(|property| result.discovery(property.name()).map(|path| (scenario.clone(), property.name(), path)));
+        }
+    }
+
+    let verdict = |pass: bool| if pass { Verdict::Pass } else { Verdict::Fail };
+    let mut report = tally.finish();
+    // A symmetric search stands one state in for all its renamings
+    report.exhaustive &= !options.symmetry;
+    if let Some(path) = &stuck_path {
+        report.counterexamples.insert("Termination", summarize_discovery(path));
+    }
+    if options.model.fair {
+        report.properties.insert("LeaderLeadsToDecision", verdict(!stranded_leader));
+    }
+    if options.model.check_liveness {
+        report.properties.insert("Termination", verdict(!stuck_run));
+    }
+
+    if let Some(dir) = cache {
+        store_cached_report(dir, &cache_key, &report)?;
+    }
+    if !emit_report(&report, &options)? {
+        return Ok(EXIT_VIOLATION);
+    }
+
+    if let Some(timeline_path) = &options.timeline {
+        match first_decision {
+            Some(path) => {
+                std::fs::write(timeline_path, decision_timeline_csv(path))?;
+                if text {
+                    println!("\nWrote decision timeline to {}", timeline_path);
+                }
+            }
+            None if text => println!("\nNo decision reached, nothing written to {}", timeline_path),
+            None => {}
+        }
+    }
+
+    if let Some(dot_path) = &options.export_dot {
+        let graph = explore_state_graph(&scenarios[0].build_model(), options.dot_max_nodes);
+        std::fs::write(dot_path, to_dot(&graph))?;
+        if text {
+            let cap = if graph.truncated { " (capped by --dot-max-nodes)" } else { "" };
+            println!("\nWrote state graph ({} states{}) to {}", graph.nodes.len(), cap, dot_path);
+        }
+    }
+
+    match &first_violation {
+        Some((scenario, name, path)) if text => {
+            println!("\nCounterexample for {}:", name);
+            print!("{}", render_trace(scenario, &deliveries_from_path(path.clone())));
+        }
+        _ => {}
+    }
+
+    if let Some(trace_path) = &options.trace_out {
+        match &first_violation {
+            Some((_, name, path)) => {
+                let steps = trace_steps(path.clone());
+                std::fs::write(trace_path, serde_json::to_string_pretty(&steps)?)?;
+                if text {
+                    println!("\nWrote {} counterexample ({} steps) to {}", name, steps.len(), trace_path);
+                }
+            }
+            None if text => println!("\nNo safety violation found, nothing written to {}", trace_path),
+            None => {}
+        }
+    }
+
+    if let Some(repro_path) = options.emit_repro {
+        match first_violation {
+            Some((scenario, name, path)) => {
+                let deliveries = deliveries_from_path(path);
+                let source = render_repro_test(&scenario, name, &deliveries)
+                    .expect("safety properties always render");
+                std::fs::write(&repro_path, source)?;
+                if text {
+                    println!("\nWrote {} reproduction ({} deliveries) to {}", name, deliveries.len(), repro_path);
+                }
+            }
+            None if text => println!("\nNo safety violation found, nothing written to {}", repro_path),
+            None => {}
+        }
+    }
+
+    if banners {
+        println!("\n=== Model Checking Complete ===");
+        println!("\nNote: With 3 nodes and message losses, liveness may not always be achievable.");
+        println!("This demonstrates the FLP impossibility theorem in practice.");
+    }
+    Ok(report.exit_code())
+}
+
+/// Prints `report` as text or JSON. False if it fails `--min-states`.
+fn emit_report(report: &CheckReport, options: &CheckOptions) -> Result<bool, Box<dyn std::error::Error>> {
+    let text = options.format == Format::Text;
+    let too_few_states = options.min_states.and_then(|min| check_min_states(report.states_explored, min).err());
+    if text {
+        print_report(report, too_few_states.as_deref(), options);
     } else {
-        println!("\n[PASS] Agreement property holds");
+        println!("{}", serde_json::to_string_pretty(report)?);
     }
+    if let Some(e) = &too_few_states {
+        if !text {
+            eprintln!("{}", e);
+        }
+    }
+    Ok(too_few_states.is_none())
+}
 
-    if let Some(_discovery) = result.discovery("Validity") {
-        println!("[FAIL] Validity property violated!");
+fn print_timing(report: &CheckReport) {
+    if report.from_cache {
+        println!("Time: none, loaded from cache (the original check took {:.2?})", report.duration);
     } else {
-        println!("[PASS] Validity property holds");
+        println!("Time: {:.2?} ({:.0} states/sec)", report.duration, report.states_per_sec());
+    }
+}
+
+fn print_exploration(report: &CheckReport, options: &CheckOptions) {
+    if report.exhaustive {
+        println!("Exploration: exhaustive, every reachable state checked");
+        return;
     }
+    let mut bounds = Vec::new();
+    if report.truncated {
+        bounds.push("stopped at --max-states");
+    }
+    if options.max_depth.is_some() {
+        bounds.push("bounded by --max-depth");
+    }
+    // Paxos has no duplication support
+    if options.model.config().max_dupes.is_some() && matches!(options.protocol, Protocol::Pbft) {
+        bounds.push("duplicates capped");
+    }
+    if options.model.adversary {
+        bounds.push("at most one message withheld");
+    }
+    if options.symmetry {
+        bounds.push("symmetric states merged");
+    }
+    println!("Exploration: INCOMPLETE, {}; a pass only covers the states seen", bounds.join(", "));
+}
 
-    if let Some(_discovery) = result.discovery("Progress") {
+fn print_safety_margin(report: &CheckReport) {
+    println!("Safety margin: {} faulty node(s) leave no all-honest quorum, so Agreement could break", report.safety_margin);
+}
+
+/// Human-readable results; `too_few_states` is the `--min-states` failure,
+/// which stops the run before the properties are listed
+fn print_report(report: &CheckReport, too_few_states: Option<&str>, options: &CheckOptions) {
+    if !options.quiet {
+        println!("\n=== Results ===");
+    }
+    println!("States explored: {}", report.states_explored);
+    print_timing(report);
+    print_exploration(report, options);
+    print_safety_margin(report);
+    if let Some(e) = too_few_states {
+        println!("[FAIL] {}", e);
+        return;
+    }
+
+    // Check for discoveries
+    println!();
+    for property in SafetyProperty::ALL {
+        if report.failed(property.name()) {
+            println!("[FAIL] {} property violated!", property.name());
+            if let Some(summary) = report.counterexamples.get(property.name()) {
+                print!("  {}: {}", report.counterexample_label(), summary);
+            }
+        } else {
+            println!("[PASS] {} property holds {}", property.name(), report.qualifier());
+        }
+    }
+
+    if report.properties.get("Progress") == Some(&Verdict::Pass) {
         println!("[PASS] Progress property satisfied");
         println!("  At least one node decided on a value");
+        match report.consensus {
+            Some(value) => println!("  Consensus reached on {}: a quorum decided it", value),
+            None => println!("  No run found where a quorum decided the same value"),
+        }
     } else {
-        println!("[PENDING] Progress property not demonstrated");
+        println!("[PENDING] Progress property not demonstrated {}", report.qualifier());
+    }
+
+    match report.properties.get("LeaderLeadsToDecision") {
+        Some(Verdict::Pass) => println!("[PASS] LeaderLeadsToDecision holds {}", report.qualifier()),
+        Some(_) => println!("[FAIL] LeaderLeadsToDecision: a run ends with a Leader but no decision"),
+        None => {}
+    }
+
+    match report.properties.get("Termination") {
+        Some(Verdict::Pass) => println!("[PASS] Termination holds {}", report.qualifier()),
+        Some(_) => {
+            println!("[FAIL] Termination: a run ends with a live node still undecided");
+            if let Some(summary) = report.counterexamples.get("Termination") {
+                print!("  {}: {}", report.counterexample_label(), summary);
+            }
+        }
+        None => {}
     }
 
-    println!("\n=== Model Checking Complete ===");
-    println!("\nNote: With 3 nodes and message losses, liveness may not always be achievable.");
-    println!("This demonstrates the FLP impossibility theorem in practice.");
+    if !report.messages.is_empty() {
+        let breakdown: Vec<String> = MESSAGE_KINDS
+            .iter()
+            .filter_map(|kind| report.messages.get(kind).map(|count| format!("{}: {}", kind, count)))
+            .collect();
+        println!("\nMessages sent on the first decision path: {}", breakdown.join(", "));
+    }
+    if let Some(depth) = report.decision_depth {
+        println!("Decision depth: {} deliveries before the first decision", depth);
+    }
+    if !report.decisions.is_empty() {
+        let tally: Vec<String> = report.decisions.iter().map(|(value, count)| format!("{}: {}", value, count)).collect();
+        println!("Decisions per value: {}", tally.join(", "));
+    }
 }
 
-fn run_explorer() {
+/// Same scenarios as `run_checker`, run through single-decree
+/// Paxos. Returns the exit code the same way, `--min-states` included.
+fn run_paxos_checker(options: CheckOptions, config: SimConfig) -> Result<i32, Box<dyn std::error::Error>> {
+    // Paxos has no duplication support, so max_dupes goes unused
+    let scenarios = options.model.scenarios(&config);
+    let text = options.format == Format::Text;
+
+    if text && !options.quiet {
+        println!("=== Consensus Protocol Model Checker ===");
+        println!("Protocol: single-decree Paxos");
+        println!("Nodes: {}", config.nodes);
+        println!("Values: {:?} ({} scenarios)", config.values, scenarios.len());
+        print_proposal(&options);
+        print_network(&options);
+        print_loss(&options);
+        print_search(&options);
+        println!();
+    }
+
+    let mut states = 0;
+    let mut progress = false;
+    let mut failed: Vec<&'static str> = Vec::new();
+    let mut truncated = false;
+    let mut depth_bounded = false;
+    let started = std::time::Instant::now();
+    for scenario in &scenarios {
+        let result = run_search(
+            paxos::paxos_model(scenario),
+            scenario.search,
+            scenario.max_depth,
+            scenario.max_states,
+            options.threads,
+        );
+        states += result.unique_state_count();
+        truncated |= hit_state_cap(result.unique_state_count(), scenario.max_states);
+        depth_bounded |= hit_depth_bound(result.max_depth(), scenario.max_depth);
+        progress |= result.discovery("Progress").is_some();
+        for name in ["Agreement", "Validity"] {
+            if result.discovery(name).is_some() && !failed.contains(&name) {
+                failed.push(name);
+            }
+        }
+    }
+
+    let verdict = |pass: bool| if pass { Verdict::Pass } else { Verdict::Fail };
+    let mut report = CheckReport {
+        states_explored: states,
+        properties: ["Agreement", "Validity"].into_iter().map(|name| (name, verdict(!failed.contains(&name)))).collect(),
+        duration: started.elapsed(),
+        truncated,
+        exhaustive: !truncated && !depth_bounded,
+        // The quorum `paxos_model` gives every acceptor
+        safety_margin: min_faulty_to_break_agreement(config.nodes, &Threshold(config.quorum_size())),
+        shortest: config.search == Search::Bfs,
+        ..CheckReport::default()
+    };
+    report.properties.insert("Progress", if progress { Verdict::Pass } else { Verdict::Pending });
+    let too_few_states = options.min_states.and_then(|min| check_min_states(report.states_explored, min).err());
+    if !text {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if let Some(e) = &too_few_states {
+            eprintln!("{}", e);
+            return Ok(EXIT_VIOLATION);
+        }
+        return Ok(report.exit_code());
+    }
+
+    if !options.quiet {
+        println!("=== Results ===");
+    }
+    println!("States explored: {}", report.states_explored);
+    print_timing(&report);
+    print_exploration(&report, &options);
+    print_safety_margin(&report);
+    if let Some(e) = too_few_states {
+        println!("[FAIL] {}", e);
+        return Ok(EXIT_VIOLATION);
+    }
+    println!();
+    for name in ["Agreement", "Validity"] {
+        if report.failed(name) {
+            println!("[FAIL] {} property violated!", name);
+        } else {
+            println!("[PASS] {} property holds {}", name, report.qualifier());
+        }
+    }
+    if report.properties.get("Progress") == Some(&Verdict::Pass) {
+        println!("[PASS] Progress property satisfied");
+    } else {
+        println!("[PENDING] Progress property not demonstrated {}", report.qualifier());
+    }
+    Ok(report.exit_code())
+}
+
+fn run_simulation(nodes: usize, seed: u64, max_steps: usize, drop_vote_prob: f64, schedule: Schedule) {
+    println!("=== Simulating One Run ===");
+    println!("Nodes: {}, seed: {}", nodes, seed);
+    if schedule != Schedule::Random {
+        println!("Schedule: {}", schedule.name());
+    }
+    if drop_vote_prob > 0.0 {
+        println!("Each node drops its Vote with probability {}", drop_vote_prob);
+    }
+    println!();
+
+    let mut model = build_model(nodes, false);
+    model.actors = model.actors.into_iter().map(|actor| actor.with_drop_vote_prob(drop_vote_prob)).collect();
+    for line in simulate_with(&model, seed, max_steps, schedule) {
+        println!("{}", line);
+    }
+}
+
+fn run_explorer(initial_path: &[usize], options: &ModelOptions, config: &SimConfig) -> Result<(), String> {
     println!("=== Launching Stateright Explorer ===");
-    println!("Opening web UI at http://localhost:3000");
+    println!("Nodes: {}", config.nodes);
     println!("Press Ctrl+C to stop\n");
 
-    let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
-    
-    ActorModel::new((), ())
-        .actor(ConsensusActor::new(peer_ids.clone()))
-        .actor(ConsensusActor::new(peer_ids.clone()))
-        .actor(ConsensusActor::new(peer_ids.clone()))
-        .init_network(Network::new_unordered_nonduplicating([]))
-        .property(
-            Expectation::Always,
-            "Agreement",
-            |_, state| check_agreement(&state.actor_states)
-        )
-        .property(
-            Expectation::Always,
-            "Validity",
-            |_, state| check_validity(&state.actor_states)
-        )
-        .property(
-            Expectation::Sometimes,
-            "Progress",
-            |_, state| has_decision(&state.actor_states)
-        )
-        .checker()
-        .serve("0.0.0.0:3000");
+    let model = options.model(config);
+    explore_with_initial(model, "0.0.0.0:3000", initial_path)
 }
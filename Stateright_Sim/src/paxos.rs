@@ -0,0 +1,227 @@
+// Single-decree Paxos, as a baseline to compare the PBFT-style protocol against.
+// Every node is an acceptor and a learner; nodes with a proposal are also
+// proposers. Proposers get one ballot each and never retry, which is enough
+// for two of them to race and exercise the safety argument.
+//
+// Phase 1: Prepare(b) -> Promise(b, highest accepted)
+// Phase 2: Accept(b, v) -> Accepted(b, v)
+// A proposer that collects a quorum of Accepted decides and tells everyone.
+
+use crate::{Decision, SimConfig, Value};
+use stateright::actor::{Actor, ActorModel, Id, LossyNetwork, Network, Out};
+use stateright::Expectation;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Ballots are ordered by round first, proposer second, so two proposers
+/// can never share one
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Ballot {
+    pub round: u64,
+    pub leader: Id,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PaxosMsg {
+    Prepare { ballot: Ballot },
+    /// `accepted` is the acceptor's highest accepted proposal, if any
+    Promise { ballot: Ballot, accepted: Option<(Ballot, Value)> },
+    Accept { ballot: Ballot, value: Value },
+    Accepted { ballot: Ballot, value: Value },
+    Decide { value: Value },
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct PaxosState {
+    // Acceptor
+    pub promised: Option<Ballot>,
+    pub accepted: Option<(Ballot, Value)>,
+    // Proposer
+    pub ballot: Option<Ballot>,
+    pub promises: BTreeMap<Id, Option<(Ballot, Value)>>,
+    /// Value sent in phase 2, once a quorum promised
+    pub proposing: Option<Value>,
+    pub accepts: BTreeSet<Id>,
+    // Learner
+    pub decided_value: Option<Value>,
+}
+
+impl Decision for PaxosState {
+    type Value = Value;
+    fn decided_value(&self) -> Option<Value> {
+        self.decided_value
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PaxosActor {
+    pub peer_ids: Vec<Id>,
+    pub quorum_size: usize,
+    pub proposal: Option<Value>,
+}
+
+impl PaxosActor {
+    pub fn new(peer_ids: Vec<Id>) -> Self {
+        let quorum_size = peer_ids.len() / 2 + 1;
+        PaxosActor {
+            peer_ids,
+            quorum_size,
+            proposal: None,
+        }
+    }
+
+    pub fn with_proposal(mut self, value: Value) -> Self {
+        self.proposal = Some(value);
+        self
+    }
+}
+
+impl Actor for PaxosActor {
+    type Msg = PaxosMsg;
+    type State = PaxosState;
+    type Timer = ();
+
+    fn on_start(&self, id: Id, o: &mut Out<Self>) -> Self::State {
+        let mut state = PaxosState::default();
+        if self.proposal.is_some() {
+            // We're an acceptor too, so the Prepare goes to ourselves as well
+            let ballot = Ballot { round: 1, leader: id };
+            state.ballot = Some(ballot);
+            o.broadcast(&self.peer_ids, &PaxosMsg::Prepare { ballot });
+        }
+        state
+    }
+
+    fn on_msg(
+        &self,
+        id: Id,
+        state: &mut Cow<Self::State>,
+        src: Id,
+        msg: Self::Msg,
+        o: &mut Out<Self>,
+    ) {
+        match msg {
+            PaxosMsg::Prepare { ballot } => {
+                if state.promised.is_none_or(|p| ballot > p) {
+                    let state = state.to_mut();
+                    state.promised = Some(ballot);
+                    o.send(src, PaxosMsg::Promise { ballot, accepted: state.accepted });
+                }
+            }
+            PaxosMsg::Promise { ballot, accepted } => {
+                if state.ballot == Some(ballot) && state.proposing.is_none() {
+                    let state = state.to_mut();
+                    state.promises.insert(src, accepted);
+                    if state.promises.len() >= self.quorum_size {
+                        // Must carry on the highest ballot's value if anyone accepted one
+                        let value = state
+                            .promises
+                            .values()
+                            .flatten()
+                            .max_by_key(|(b, _)| *b)
+                            .map(|&(_, v)| v)
+                            .or(self.proposal)
+                            .expect("only proposers hold a ballot");
+                        state.proposing = Some(value);
+                        o.broadcast(&self.peer_ids, &PaxosMsg::Accept { ballot, value });
+                    }
+                }
+            }
+            PaxosMsg::Accept { ballot, value } => {
+                if state.promised.is_none_or(|p| ballot >= p) {
+                    let state = state.to_mut();
+                    state.promised = Some(ballot);
+                    state.accepted = Some((ballot, value));
+                    o.send(src, PaxosMsg::Accepted { ballot, value });
+                }
+            }
+            PaxosMsg::Accepted { ballot, value } => {
+                if state.ballot == Some(ballot) {
+                    let state = state.to_mut();
+                    state.accepts.insert(src);
+                    if state.accepts.len() >= self.quorum_size && state.decided_value.is_none() {
+                        state.decided_value = Some(value);
+                        for &peer in self.peer_ids.iter().filter(|&&p| p != id) {
+                            o.send(peer, PaxosMsg::Decide { value });
+                        }
+                    }
+                }
+            }
+            PaxosMsg::Decide { value } => {
+                if state.decided_value.is_none() {
+                    state.to_mut().decided_value = Some(value);
+                }
+            }
+        }
+    }
+}
+
+pub type PaxosModel = ActorModel<PaxosActor, (), ()>;
+
+/// Paxos counterpart of [`SimConfig::build_model`]. Uses the node count,
+/// quorum, proposer, rival, all-propose, loss and ordering settings; crashes, partitions,
+/// Byzantine faults and duplication only apply to the PBFT-style protocol.
+pub fn paxos_model(config: &SimConfig) -> PaxosModel {
+    let peer_ids: Vec<Id> = (0..config.nodes).map(Id::from).collect();
+    let actors = (0..config.nodes).map(|i| {
+        let actor = PaxosActor {
+            quorum_size: config.quorum_size(),
+            ..PaxosActor::new(peer_ids.clone())
+        };
+        match config.rival {
+            _ if config.all_propose => actor.with_proposal(config.values[i]),
+            _ if i == config.proposer => actor.with_proposal(config.proposal),
+            Some((node, value)) if node == i => actor.with_proposal(value),
+            _ => actor,
+        }
+    });
+    let lossy = if config.loss_rate > 0.0 { LossyNetwork::Yes } else { LossyNetwork::No };
+
+    ActorModel::new((), ())
+        .actors(actors)
+        .init_network(if config.ordered { Network::new_ordered([]) } else { Network::new_unordered_nonduplicating([]) })
+        .lossy_network(lossy)
+        .property(Expectation::Always, "Agreement", |_, state| {
+            crate::check_agreement(&state.actor_states)
+        })
+        .property(Expectation::Always, "Validity", |model, state| {
+            let proposed: Vec<Value> = model.actors.iter().filter_map(|a| a.proposal).collect();
+            crate::check_validity(&state.actor_states, &proposed)
+        })
+        .property(Expectation::Sometimes, "Progress", |_, state| {
+            crate::has_decision(&state.actor_states)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stateright::{Checker, Model};
+
+    #[test]
+    fn test_paxos_safe_under_contention() {
+        let config = SimConfig {
+            rival: Some((1, Value::V1)),
+            ..SimConfig::default()
+        };
+        let result = paxos_model(&config).checker().spawn_bfs().join();
+
+        assert!(result.discovery("Agreement").is_none(), "Agreement violated");
+        assert!(result.discovery("Validity").is_none(), "Validity violated");
+        assert!(result.discovery("Progress").is_some(), "Paxos should be able to decide");
+    }
+
+    #[test]
+    fn test_paxos_single_proposer_decides_its_value() {
+        let model = paxos_model(&SimConfig::default());
+        let result = model.checker().spawn_bfs().join();
+        let path = result.discovery("Progress").expect("a decision is reachable");
+        let decided: Vec<_> = path
+            .last_state()
+            .actor_states
+            .iter()
+            .filter_map(|s| s.decided_value)
+            .collect();
+        assert_eq!(decided, vec![Value::V0]);
+    }
+}
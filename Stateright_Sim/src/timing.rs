@@ -0,0 +1,313 @@
+// The consensus model with a clock. Plain `ConsensusModel` lets any message
+// arrive and any timer fire in any order, so an election timeout can go off
+// while the proposal it's waiting for is one step away. Here every message
+// in flight carries the time it arrives and every timer the time it fires,
+// and only the earliest of those events can happen next; delays and timeouts
+// become comparable, so a run only elects a new candidate when the proposal
+// really is later than the timeout.
+//
+// Wraps the actor model rather than changing it: ActorModel has no hook
+// between an action and the timers it sets. Assumes an unordered,
+// non-duplicating network, the only kind where a send is one arrival.
+
+use crate::{ConsensusModel, ConsensusModelState, ConsensusMsg, SafetyProperty, Timer};
+use stateright::actor::{ActorModelAction, Envelope, Id};
+use stateright::{Expectation, Model, Property};
+use std::collections::BTreeMap;
+
+/// A named property over timed states, as [`TimedModel::property`] takes it
+pub type TimedPropertySpec = (Expectation, &'static str, fn(&TimedModel, &TimedState) -> bool);
+
+/// The global state plus the logical clock and every pending deadline
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TimedState {
+    pub inner: ConsensusModelState,
+    /// Time of the last delivery or timeout; drops and crashes take none
+    pub clock: u64,
+    /// Arrival times of each envelope in flight, earliest first, one per copy
+    pub arrivals: BTreeMap<Envelope<ConsensusMsg>, Vec<u64>>,
+    /// When each armed timer fires
+    pub deadlines: BTreeMap<(Id, Timer), u64>,
+}
+
+impl TimedState {
+    /// When the next delivery or timeout is due, if anything is pending
+    pub fn next_event(&self) -> Option<u64> {
+        let arrival = self.arrivals.values().filter_map(|times| times.first()).min();
+        arrival.into_iter().chain(self.deadlines.values()).min().copied()
+    }
+}
+
+/// [`ConsensusModel`] with per-link message delays and timer durations, all
+/// in the same discrete time units
+#[derive(Clone)]
+pub struct TimedModel {
+    pub model: ConsensusModel,
+    /// Delay of any link without its own entry in `link_delays`
+    pub delay: u64,
+    pub link_delays: BTreeMap<(Id, Id), u64>,
+    pub election_timeout: u64,
+    pub retry_timeout: u64,
+    /// Don't explore past this time; `None` means unbounded, which only
+    /// terminates if the nodes eventually stop re-arming their timers
+    pub horizon: Option<u64>,
+    pub properties: Vec<TimedPropertySpec>,
+}
+
+impl TimedModel {
+    /// Every link delays by 1 and every timer runs for 4, with the safety
+    /// properties checked on the wrapped states
+    pub fn new(model: ConsensusModel) -> Self {
+        let properties = SafetyProperty::ALL
+            .into_iter()
+            .map(|property| (Expectation::Always, property.name(), safety_condition(property)))
+            .collect();
+        TimedModel {
+            model,
+            delay: 1,
+            link_delays: BTreeMap::new(),
+            election_timeout: 4,
+            retry_timeout: 4,
+            horizon: None,
+            properties,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: u64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Delay of messages from `src` to `dst` only
+    pub fn with_link_delay(mut self, src: Id, dst: Id, delay: u64) -> Self {
+        self.link_delays.insert((src, dst), delay);
+        self
+    }
+
+    pub fn with_election_timeout(mut self, timeout: u64) -> Self {
+        self.election_timeout = timeout;
+        self
+    }
+
+    pub fn with_retry_timeout(mut self, timeout: u64) -> Self {
+        self.retry_timeout = timeout;
+        self
+    }
+
+    pub fn with_horizon(mut self, horizon: u64) -> Self {
+        self.horizon = Some(horizon);
+        self
+    }
+
+    pub fn property(
+        mut self,
+        expectation: Expectation,
+        name: &'static str,
+        condition: fn(&TimedModel, &TimedState) -> bool,
+    ) -> Self {
+        self.properties.push((expectation, name, condition));
+        self
+    }
+
+    pub fn link_delay(&self, src: Id, dst: Id) -> u64 {
+        self.link_delays.get(&(src, dst)).copied().unwrap_or(self.delay)
+    }
+
+    pub fn timeout(&self, timer: Timer) -> u64 {
+        match timer {
+            Timer::Election => self.election_timeout,
+            Timer::CommitRetry => self.retry_timeout,
+        }
+    }
+
+    /// Stamps whatever `inner` has in flight or armed beyond what `last`
+    /// already accounts for, as sent or set at `clock`. A timer that `fired`
+    /// and is armed again counts as newly set.
+    fn stamp(&self, last: &Pending, inner: ConsensusModelState, clock: u64, fired: Option<(Id, Timer)>) -> TimedState {
+        let mut in_flight: BTreeMap<Envelope<ConsensusMsg>, usize> = BTreeMap::new();
+        for env in inner.network.iter_all() {
+            *in_flight.entry(env.to_cloned_msg()).or_default() += 1;
+        }
+        let arrivals = in_flight
+            .into_iter()
+            .map(|(env, copies)| {
+                let mut times = last.arrivals.get(&env).cloned().unwrap_or_default();
+                times.truncate(copies);
+                let arrival = clock + self.link_delay(env.src, env.dst);
+                times.resize(copies, arrival);
+                times.sort_unstable();
+                (env, times)
+            })
+            .collect();
+
+        let deadlines = inner
+            .timers_set
+            .iter()
+            .enumerate()
+            .flat_map(|(index, timers)| timers.iter().map(move |&timer| (Id::from(index), timer)))
+            .map(|key| {
+                let kept = last.deadlines.get(&key).filter(|_| fired != Some(key));
+                (key, kept.copied().unwrap_or(clock + self.timeout(key.1)))
+            })
+            .collect();
+
+        TimedState { inner, clock, arrivals, deadlines }
+    }
+}
+
+/// The deadlines of a [`TimedState`], minus its model state
+#[derive(Default)]
+struct Pending {
+    arrivals: BTreeMap<Envelope<ConsensusMsg>, Vec<u64>>,
+    deadlines: BTreeMap<(Id, Timer), u64>,
+}
+
+/// Like [`SafetyProperty::condition`], on the states a [`TimedModel`] wraps
+fn safety_condition(property: SafetyProperty) -> fn(&TimedModel, &TimedState) -> bool {
+    match property {
+        SafetyProperty::Agreement => |m, s| SafetyProperty::Agreement.eval(&m.model.cfg, &s.inner.actor_states),
+        SafetyProperty::Validity => |m, s| (SafetyProperty::Validity.condition())(&m.model, &s.inner),
+        SafetyProperty::TotalVotesBound => |m, s| SafetyProperty::TotalVotesBound.eval(&m.model.cfg, &s.inner.actor_states),
+        SafetyProperty::SameViewQuorum => |m, s| SafetyProperty::SameViewQuorum.eval(&m.model.cfg, &s.inner.actor_states),
+        SafetyProperty::Integrity => |m, s| SafetyProperty::Integrity.eval(&m.model.cfg, &s.inner.actor_states),
+        SafetyProperty::SingleLeaderPerTerm => |m, s| SafetyProperty::SingleLeaderPerTerm.eval(&m.model.cfg, &s.inner.actor_states),
+        SafetyProperty::LeaderCommitsProposal => {
+            |m, s| (SafetyProperty::LeaderCommitsProposal.condition())(&m.model, &s.inner)
+        }
+        SafetyProperty::NoSafetyTrap => |m, s| SafetyProperty::NoSafetyTrap.eval(&m.model.cfg, &s.inner.actor_states),
+        SafetyProperty::CrashedNeverVote => {
+            |m, s| (SafetyProperty::CrashedNeverVote.condition())(&m.model, &s.inner)
+        }
+    }
+}
+
+impl Model for TimedModel {
+    type State = TimedState;
+    type Action = ActorModelAction<ConsensusMsg, Timer>;
+
+    fn init_states(&self) -> Vec<Self::State> {
+        self.model
+            .init_states()
+            .into_iter()
+            .map(|inner| self.stamp(&Pending::default(), inner, 0, None))
+            .collect()
+    }
+
+    fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+        let now = state.next_event();
+        let mut inner = Vec::new();
+        self.model.actions(&state.inner, &mut inner);
+        // Losing a message or crashing isn't an event on the clock
+        actions.extend(inner.into_iter().filter(|action| match action {
+            ActorModelAction::Deliver { src, dst, msg } => {
+                let env = Envelope { src: *src, dst: *dst, msg: msg.clone() };
+                state.arrivals.get(&env).and_then(|times| times.first()) == now.as_ref()
+            }
+            ActorModelAction::Timeout(id, timer) => state.deadlines.get(&(*id, *timer)) == now.as_ref(),
+            ActorModelAction::Drop(_) | ActorModelAction::Crash(_) => true,
+        }));
+    }
+
+    fn next_state(&self, last: &Self::State, action: Self::Action) -> Option<Self::State> {
+        let mut pending = Pending { arrivals: last.arrivals.clone(), deadlines: last.deadlines.clone() };
+        match &action {
+            ActorModelAction::Deliver { src, dst, msg } => {
+                let env = Envelope { src: *src, dst: *dst, msg: msg.clone() };
+                let clock = pending.arrivals.get_mut(&env)?.remove(0);
+                // The actor model skips deliveries that change nothing, but
+                // here the message still has to leave the network, or the
+                // clock could never pass its arrival time
+                let inner = self
+                    .model
+                    .next_state(&last.inner, action.clone())
+                    .or_else(|| self.model.next_state(&last.inner, ActorModelAction::Drop(env)))?;
+                Some(self.stamp(&pending, inner, clock, None))
+            }
+            ActorModelAction::Drop(env) => {
+                pending.arrivals.get_mut(env)?.remove(0);
+                let inner = self.model.next_state(&last.inner, action.clone())?;
+                Some(self.stamp(&pending, inner, last.clock, None))
+            }
+            &ActorModelAction::Timeout(id, timer) => {
+                let clock = *pending.deadlines.get(&(id, timer))?;
+                // Likewise a timer that only re-arms itself, which here moves its deadline
+                let inner = self.model.next_state(&last.inner, action).unwrap_or_else(|| last.inner.clone());
+                Some(self.stamp(&pending, inner, clock, Some((id, timer))))
+            }
+            ActorModelAction::Crash(_) => {
+                let inner = self.model.next_state(&last.inner, action)?;
+                Some(self.stamp(&pending, inner, last.clock, None))
+            }
+        }
+    }
+
+    fn format_action(&self, action: &Self::Action) -> String {
+        self.model.format_action(action)
+    }
+
+    fn properties(&self) -> Vec<Property<Self>> {
+        self.properties
+            .iter()
+            .map(|(expectation, name, condition)| Property {
+                expectation: expectation.clone(),
+                name,
+                condition: *condition,
+            })
+            .collect()
+    }
+
+    fn within_boundary(&self, state: &Self::State) -> bool {
+        self.horizon.is_none_or(|horizon| state.clock <= horizon) && Model::within_boundary(&self.model, &state.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConsensusActor, DeliveryCounts, ModelConfig, NodeRole};
+    use stateright::actor::{ActorModel, Network};
+    use stateright::Checker;
+
+    /// Node 0 proposes at start; nodes 1 and 2 run for election if nothing
+    /// reaches them in time
+    fn cluster() -> ConsensusModel {
+        let peer_ids: Vec<Id> = (0..3).map(Id::from).collect();
+        let cfg = ModelConfig { nodes: 3, weights: vec![1; 3], quorum: 2, ..ModelConfig::default() };
+        ActorModel::new(cfg, DeliveryCounts::new(None))
+            .actor(ConsensusActor::new(peer_ids.clone()))
+            .actors((1..3).map(|_| ConsensusActor::new(peer_ids.clone()).with_election_timeout(true)))
+            .init_network(Network::new_unordered_nonduplicating([]))
+    }
+
+    fn timed(delay: u64) -> TimedModel {
+        TimedModel::new(cluster())
+            .with_delay(delay)
+            .with_election_timeout(5)
+            .with_horizon(30)
+            .property(Expectation::Sometimes, "NewCandidate", |_, state| {
+                state.inner.actor_states[1..].iter().any(|s| s.role == NodeRole::Candidate || s.term > 0)
+            })
+    }
+
+    #[test]
+    fn test_slow_proposal_triggers_election() {
+        // Untimed, the timeout can fire whenever, however prompt the network
+        let untimed = cluster()
+            .property(Expectation::Sometimes, "NewCandidate", |_, state| {
+                state.actor_states[1..].iter().any(|s| s.term > 0)
+            })
+            .checker()
+            .spawn_bfs()
+            .join();
+        assert!(untimed.discovery("NewCandidate").is_some());
+
+        let prompt = timed(1).checker().spawn_bfs().join();
+        assert!(prompt.discovery("NewCandidate").is_none(), "the proposal beats a timeout of 5");
+
+        let slow = timed(10).checker().spawn_bfs().join();
+        let path = slow.discovery("NewCandidate").expect("a delay of 10 outlasts a timeout of 5");
+        let last = path.last_state();
+        assert!(last.clock >= 5 && last.clock < 10, "elected at {}, before the proposal arrived", last.clock);
+        assert!(slow.discovery("Agreement").is_none());
+    }
+}
@@ -0,0 +1,288 @@
+// The protocol's message handling as a plain function, for reuse outside the
+// checker, e.g. in a WASM or embedded node that brings its own networking.
+// `step` takes a node's state, a message and the quorum it counts votes
+// against, and hands back the next state plus whatever the node wants sent,
+// with no Actor, Out or model in sight. `ConsensusActor::on_msg` runs the
+// same handlers straight into stateright's Out through [`Outbox`].
+//
+// Not no_std yet: the state still keeps its votes in std's HashMap, and ids
+// are stateright's `Id` (a plain usize inside).
+
+use crate::{ConsensusActor, ConsensusMsg, ConsensusState, NodeRole, ProposalValue, Threshold, Timer, Value};
+use stateright::actor::Id;
+use std::collections::HashSet;
+
+/// Where the handlers put what a node asks of the world around it
+pub trait Outbox<V> {
+    fn send(&mut self, dst: Id, msg: ConsensusMsg<V>);
+    /// When the timer fires is up to whoever owns the outbox
+    fn set_timer(&mut self, timer: Timer);
+}
+
+/// What a transition asks of the world around the node, in the order asked
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Effects<V = Value> {
+    pub sends: Vec<(Id, ConsensusMsg<V>)>,
+    /// Timers to (re)arm; when they fire is up to the caller
+    pub timers: Vec<Timer>,
+}
+
+impl<V> Default for Effects<V> {
+    fn default() -> Self {
+        Effects { sends: Vec::new(), timers: Vec::new() }
+    }
+}
+
+impl<V> Effects<V> {
+    pub fn is_empty(&self) -> bool {
+        self.sends.is_empty() && self.timers.is_empty()
+    }
+}
+
+impl<V> Outbox<V> for Effects<V> {
+    fn send(&mut self, dst: Id, msg: ConsensusMsg<V>) {
+        self.sends.push((dst, msg));
+    }
+
+    fn set_timer(&mut self, timer: Timer) {
+        self.timers.push(timer);
+    }
+}
+
+/// The cluster as one node sees it: its own id, everyone's (its own
+/// included), and how many votes make a quorum
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Quorum {
+    pub me: Id,
+    pub peers: Vec<Id>,
+    pub size: usize,
+}
+
+/// The node `quorum.me` handles `msg` from `src` in `state`, running the
+/// plain protocol: one slot, one vote per peer, none of the optional
+/// phases. Returns the next state and what to send.
+pub fn step<V: ProposalValue>(
+    state: &ConsensusState<V>,
+    src: Id,
+    msg: &ConsensusMsg<V>,
+    quorum: &Quorum,
+) -> (ConsensusState<V>, Effects<V>) {
+    let node = ConsensusActor::with_peers(quorum.peers.clone())
+        .with_leader(None)
+        .with_quorum_policy(Threshold(quorum.size));
+    node.step(quorum.me, state, src, msg)
+}
+
+/// What has to hold across any one step, whatever message caused it, in a
+/// cluster of `peers` nodes. Used by the fuzz target (`fuzz/`) and the
+/// property test below to throw arbitrary traffic at [`step`]:
+/// - a decided value never changes, and the decided log only grows
+/// - `votes_received` never counts more voters than there are peers
+/// - nothing leaves Decided or Aborted. Roles aren't monotone short of that:
+///   a newer term sends a Candidate or Leader back to Follower.
+pub fn check_step<V: ProposalValue>(
+    before: &ConsensusState<V>,
+    after: &ConsensusState<V>,
+    peers: usize,
+) -> Result<(), String> {
+    if before.decided_value.is_some() && after.decided_value != before.decided_value {
+        return Err(format!("decided {:?}, then {:?}", before.decided_value, after.decided_value));
+    }
+    if !after.decided_log.starts_with(&before.decided_log) {
+        return Err(format!("decided log {:?} rewritten to {:?}", before.decided_log, after.decided_log));
+    }
+    let voters: HashSet<Id> = after.votes_received.values().flatten().copied().collect();
+    if voters.len() > peers {
+        return Err(format!("{} voters in a {}-node cluster", voters.len(), peers));
+    }
+    if matches!(before.role, NodeRole::Decided | NodeRole::Aborted) && after.role != before.role {
+        return Err(format!("{:?} node became {:?}", before.role, after.role));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use stateright::actor::{Actor, Out};
+    use std::borrow::Cow;
+    use std::collections::BTreeSet;
+
+    fn peers() -> Vec<Id> {
+        (0..3).map(Id::from).collect()
+    }
+
+    /// Node `me` of three, counting a majority
+    fn node(me: usize) -> Quorum {
+        Quorum { me: Id::from(me), peers: peers(), size: 2 }
+    }
+
+    fn certificate(ids: &[usize]) -> BTreeSet<Id> {
+        ids.iter().copied().map(Id::from).collect()
+    }
+
+    #[test]
+    fn test_step_propose_and_vote() {
+        let propose = ConsensusMsg::Propose { value: Value::V1, term: 0, slot: 0 };
+        let (state, effects) = step(&ConsensusState::default(), Id::from(0), &propose, &node(1));
+        assert_eq!(state.proposed_value, Some(Value::V1));
+        assert_eq!(effects.sends, vec![(Id::from(0), ConsensusMsg::Vote { value: Value::V1, term: 0, slot: 0 })]);
+
+        // The vote completes node 0's quorum of 2, so it leads, commits and decides
+        let proposer = ConsensusActor::new(peers()).with_proposal(Value::V1);
+        let vote = ConsensusMsg::Vote { value: Value::V1, term: 0, slot: 0 };
+        let start = proposer.on_start(Id::from(0), &mut Out::new());
+        let (state, effects) = step(&start, Id::from(1), &vote, &node(0));
+        assert_eq!((state.role, state.decided_value), (NodeRole::Decided, Some(Value::V1)));
+        let commit = ConsensusMsg::Commit { value: Value::V1, term: 0, slot: 0, certificate: certificate(&[0, 1]) };
+        let dsts: Vec<Id> = effects.sends.iter().filter(|(_, m)| *m == commit).map(|&(dst, _)| dst).collect();
+        assert_eq!(dsts, peers()[1..], "the commit goes to everyone else");
+    }
+
+    #[test]
+    fn test_step_prepare_and_promise() {
+        let prepare = ConsensusMsg::Prepare { value: Value::V2, term: 0, slot: 0, certificate: certificate(&[0, 1]) };
+        let (state, effects) = step(&ConsensusState::default(), Id::from(0), &prepare, &node(1));
+        assert_eq!(state.locked, Some(Value::V2));
+        assert_eq!(effects.sends, vec![(Id::from(0), ConsensusMsg::Promise { value: Value::V2, term: 0, slot: 0 })]);
+
+        let leader = ConsensusState {
+            role: NodeRole::Leader,
+            locked: Some(Value::V2),
+            promises: certificate(&[0]),
+            votes_received: [(Value::V2, [Id::from(0), Id::from(1)].into())].into(),
+            ..ConsensusState::default()
+        };
+        let promise = ConsensusMsg::Promise { value: Value::V2, term: 0, slot: 0 };
+        let (state, effects) = step(&leader, Id::from(1), &promise, &node(0));
+        assert_eq!(state.promises, certificate(&[0, 1]));
+        assert!(effects.sends.iter().all(|(_, m)| m.kind() == "Commit") && effects.sends.len() == 2);
+        assert_eq!(state.decided_value, Some(Value::V2));
+    }
+
+    #[test]
+    fn test_step_commit_and_ack() {
+        let commit = ConsensusMsg::Commit { value: Value::V0, term: 0, slot: 0, certificate: certificate(&[0, 1]) };
+        let (state, effects) = step(&ConsensusState::default(), Id::from(0), &commit, &node(2));
+        assert_eq!((state.role, state.decided_value), (NodeRole::Decided, Some(Value::V0)));
+        assert!(effects.is_empty(), "plain nodes don't acknowledge");
+
+        // Acks only come with retries, which need the actor's settings
+        let actor = ConsensusActor::new(peers()).with_leader(None).with_commit_retry(true);
+        let (_, effects) = actor.step(Id::from(2), &ConsensusState::default(), Id::from(0), &commit);
+        assert_eq!(effects.sends, vec![(Id::from(0), ConsensusMsg::CommitAck { term: 0, slot: 0 })]);
+
+        let retrying = ConsensusState {
+            pending_commit: Some(commit),
+            commit_acks: certificate(&[0]),
+            ..ConsensusState::default()
+        };
+        let (state, effects) = step(&retrying, Id::from(2), &ConsensusMsg::CommitAck { term: 0, slot: 0 }, &node(0));
+        assert_eq!(state.commit_acks, certificate(&[0, 2]));
+        assert!(state.pending_commit.is_some(), "node 1 may still be missing it");
+        assert!(effects.is_empty());
+
+        let (state, _) = step(&state, Id::from(1), &ConsensusMsg::CommitAck { term: 0, slot: 0 }, &node(0));
+        assert!(state.pending_commit.is_none(), "everyone has it, so the retries stop");
+    }
+
+    #[test]
+    fn test_step_state_transfer() {
+        let decided = ConsensusState {
+            decided_value: Some(Value::V1),
+            term: 2,
+            certificate: certificate(&[0, 1]),
+            ..ConsensusState::default()
+        };
+        let (state, effects) = step(&decided, Id::from(2), &ConsensusMsg::StateRequest, &node(1));
+        assert_eq!(state, decided);
+        let response = ConsensusMsg::StateResponse { decided_value: Some(Value::V1), view: 2, certificate: certificate(&[0, 1]) };
+        assert_eq!(effects.sends, vec![(Id::from(2), response.clone())]);
+
+        let (state, effects) = step(&ConsensusState::default(), Id::from(1), &response, &node(2));
+        assert_eq!((state.decided_value, state.term), (Some(Value::V1), 2));
+        assert!(effects.is_empty());
+
+        // One peer's word isn't a quorum
+        let bare = ConsensusMsg::StateResponse { decided_value: Some(Value::V1), view: 2, certificate: certificate(&[1]) };
+        let (state, _) = step(&ConsensusState::default(), Id::from(1), &bare, &node(2));
+        assert!(state.decided_value.is_none());
+    }
+
+    #[test]
+    fn test_step_matches_on_msg() {
+        // Same inputs, same outputs as the actor the checker runs
+        let actor = ConsensusActor::new(peers()).with_quorum_policy(Threshold(3));
+        let start = actor.on_start(Id::from(0), &mut Out::new());
+        let vote = ConsensusMsg::Vote { value: Value::V0, term: 0, slot: 0 };
+        let (stepped, effects) = step(&start, Id::from(1), &vote, &Quorum { size: 3, ..node(0) });
+
+        let mut state = Cow::Borrowed(&start);
+        let mut out = Out::new();
+        actor.on_msg(Id::from(0), &mut state, Id::from(1), vote.clone(), &mut out);
+        assert_eq!(*state, stepped);
+        assert!(effects.is_empty() && out.is_empty(), "2 of 3 votes isn't a quorum of 3");
+
+        // The last vote it needs: the Commit goes out the same either way
+        let (stepped, effects) = step(&stepped, Id::from(2), &vote, &Quorum { size: 3, ..node(0) });
+        actor.on_msg(Id::from(0), &mut state, Id::from(2), vote, &mut out);
+        assert_eq!(*state, stepped);
+        let sent: Vec<(Id, ConsensusMsg)> = out.iter().map(|command| match command {
+            stateright::actor::Command::Send(dst, msg) => (*dst, msg.clone()),
+            other => panic!("unexpected {:?}", other),
+        }).collect();
+        assert_eq!(sent, effects.sends);
+    }
+
+    /// Node 0 bootstraps a proposal in every flavour; the flags vary the
+    /// paths a message can take through `handle_msg`
+    fn flavoured(flavour: usize) -> ConsensusActor {
+        let actor = ConsensusActor::new(peers());
+        match flavour {
+            0 => actor,
+            1 => actor.with_two_phase(true).with_commit_quorum(true),
+            2 => actor.with_commit_retry(true).with_catch_up(true).with_gossip(true).with_slots(2),
+            _ => actor.with_tie_break(true).with_abort_after(1).with_election_timeout(true),
+        }
+    }
+
+    fn any_msg() -> impl Strategy<Value = ConsensusMsg> {
+        let value = || prop::sample::select(Value::ALL.to_vec());
+        let certificate = || prop::collection::btree_set((0..3usize).prop_map(Id::from), 0..=3);
+        prop_oneof![
+            (value(), 0..3u64, 0..2u64).prop_map(|(value, term, slot)| ConsensusMsg::Propose { value, term, slot }),
+            (value(), 0..3u64, 0..2u64).prop_map(|(value, term, slot)| ConsensusMsg::Vote { value, term, slot }),
+            (value(), 0..3u64, 0..2u64, certificate())
+                .prop_map(|(value, term, slot, certificate)| ConsensusMsg::Prepare { value, term, slot, certificate }),
+            (value(), 0..3u64, 0..2u64).prop_map(|(value, term, slot)| ConsensusMsg::Promise { value, term, slot }),
+            (value(), 0..3u64, 0..2u64, certificate())
+                .prop_map(|(value, term, slot, certificate)| ConsensusMsg::Commit { value, term, slot, certificate }),
+            Just(ConsensusMsg::StateRequest),
+            (prop::option::of(value()), 0..3u64, certificate())
+                .prop_map(|(decided_value, view, certificate)| ConsensusMsg::StateResponse { decided_value, view, certificate }),
+            (0..3u64, 0..2u64).prop_map(|(term, slot)| ConsensusMsg::CommitAck { term, slot }),
+            value().prop_map(|value| ConsensusMsg::ClientRequest { value }),
+        ]
+    }
+
+    proptest! {
+        // Arbitrary traffic, forged and out-of-order messages included, at
+        // the 3-node size the checker explores exhaustively only for honest runs
+        #[test]
+        fn test_step_invariants_under_arbitrary_traffic(
+            flavour in 0..4usize,
+            traffic in prop::collection::vec((0..3usize, 0..3usize, any_msg()), 0..40),
+        ) {
+            let actor = flavoured(flavour);
+            let mut states: Vec<ConsensusState> = (0..3).map(|i| actor.on_start(Id::from(i), &mut Out::new())).collect();
+            for (src, dst, msg) in traffic {
+                let (next, _) = actor.step(Id::from(dst), &states[dst], Id::from(src), &msg);
+                if let Err(broken) = check_step(&states[dst], &next, 3) {
+                    prop_assert!(false, "node {} on {:?} from {}: {}", dst, msg, src, broken);
+                }
+                states[dst] = next;
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// `explore` shows `ModelOptions::model` of the base config while `check`
+// searches `ModelOptions::scenario_models` of each of its scenarios, so
+// nothing but this test keeps the two from checking different properties or
+// running a different network for the same flags. Only inspects the models;
+// nothing binds the explorer's port.
+
+use consensus_stateright::*;
+use std::collections::BTreeSet;
+use std::mem::discriminant;
+
+fn property_names(model: &ConsensusModel) -> BTreeSet<&'static str> {
+    model.properties.iter().map(|p| p.name).collect()
+}
+
+fn assert_same_models(options: &ModelOptions) {
+    let config = options.config();
+    let explored = options.model(&config);
+    let scenarios = options.scenarios(&config);
+    assert!(!scenarios.is_empty(), "{:?}", options);
+    for scenario in scenarios {
+        let (model, liveness) = options.scenario_models(&scenario);
+        // Split under --fair, but between them the same properties
+        let mut checked = property_names(&model);
+        checked.extend(liveness.iter().flat_map(property_names));
+        assert_eq!(checked, property_names(&explored), "{:?}", options);
+        assert_eq!(discriminant(&model.init_network), discriminant(&explored.init_network), "{:?}", options);
+        assert!(model.lossy_network == explored.lossy_network, "{:?}", options);
+        assert_eq!(model.actors.len(), explored.actors.len());
+        assert_eq!(model.cfg, explored.cfg);
+    }
+}
+
+#[test]
+fn test_explore_and_check_build_the_same_model() {
+    for nodes in 1..=4 {
+        assert_same_models(&ModelOptions { nodes, ..ModelOptions::default() });
+    }
+}
+
+#[test]
+fn test_explore_and_check_agree_on_network_and_liveness_flags() {
+    let variants = [
+        ModelOptions { network: NetworkKind::Ordered, ..ModelOptions::default() },
+        ModelOptions { network: NetworkKind::Duplicating, max_dupes: Some(3), ..ModelOptions::default() },
+        ModelOptions { loss_rate: 0.1, check_liveness: true, ..ModelOptions::default() },
+        ModelOptions { loss_rate: 0.1, fair: true, check_liveness: true, ..ModelOptions::default() },
+        ModelOptions { adversary: true, check_liveness: true, ..ModelOptions::default() },
+        ModelOptions { contention: true, ..ModelOptions::default() },
+    ];
+    for options in &variants {
+        assert_same_models(options);
+    }
+
+    // The flags really change the model, so the comparison above has teeth
+    let plain = ModelOptions::default();
+    let lossy = &variants[2];
+    assert!(plain.model(&plain.config()).lossy_network != lossy.model(&lossy.config()).lossy_network);
+    assert!(property_names(&lossy.model(&lossy.config())).contains("Termination"));
+    let ordered = &variants[0];
+    assert_ne!(
+        discriminant(&plain.model(&plain.config()).init_network),
+        discriminant(&ordered.model(&ordered.config()).init_network)
+    );
+}
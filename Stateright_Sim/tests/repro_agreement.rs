@@ -0,0 +1,46 @@
+// Generated by `consensus check --emit-repro` - violation of Agreement
+
+use consensus_stateright::*;
+
+#[test]
+fn repro_agreement() {
+    let config = SimConfig::from_json(r#"{"nodes":3,"quorum":null,"proposer":0,"proposal":"V0","rival":null,"contention":false,"values":["V0","V1","V2"],"crashed":[],"recovering":[],"byzantine_faults":0,"partitions":[],"loss_rate":0.0,"max_dupes":null,"ordered":false,"all_propose":false,"adversary":false,"search":"bfs","max_depth":null,"max_states":null,"views":{}}"#).unwrap();
+    let deliveries: Vec<DeliveryRecord> = serde_json::from_str(
+        r#"[
+  {
+    "src": 0,
+    "dst": 1,
+    "msg": {
+      "Commit": {
+        "value": "V0",
+        "term": 0,
+        "slot": 0,
+        "certificate": [
+          0,
+          1
+        ]
+      }
+    }
+  },
+  {
+    "src": 0,
+    "dst": 2,
+    "msg": {
+      "Commit": {
+        "value": "V1",
+        "term": 0,
+        "slot": 0,
+        "certificate": [
+          0,
+          2
+        ]
+      }
+    }
+  }
+]"#,
+    )
+    .unwrap();
+
+    let states = replay(&config, &deliveries);
+    assert!(!SafetyProperty::Agreement.eval(&config.model_config(), &states), "expected Agreement to be violated");
+}